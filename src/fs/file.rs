@@ -15,6 +15,9 @@ use crate::prelude::*;
 use crate::task::{self, spawn_blocking, Context, Poll, Waker};
 use crate::utils::Context as _;
 
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+use crate::net::driver::uring::Ops;
+
 /// An open file on the filesystem.
 ///
 /// Depending on what options the file was opened with, this type can be used for reading and/or
@@ -69,12 +72,22 @@ pub struct File {
 impl File {
     /// Creates an async file handle.
     pub(crate) fn new(file: std::fs::File, is_flushed: bool) -> File {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        let ops = {
+            use std::os::unix::io::AsRawFd;
+            Ops::new(file.as_raw_fd())
+        };
+
         let file = Arc::new(file);
 
         File {
             file: file.clone(),
             lock: Lock::new(State {
                 file,
+                #[cfg(all(target_os = "linux", feature = "io-uring"))]
+                ops,
+                #[cfg(all(target_os = "linux", feature = "io-uring"))]
+                submitted: None,
                 mode: Mode::Idle,
                 cache: Vec::new(),
                 is_flushed,
@@ -561,6 +574,82 @@ impl<T> DerefMut for LockGuard<T> {
     }
 }
 
+impl State {
+    /// Stores the result of reading into the cache.
+    fn finish_read(&mut self, res: io::Result<usize>) {
+        match res {
+            Ok(n) => {
+                // Update cache length and switch to reading mode, starting from index 0.
+                unsafe {
+                    self.cache.set_len(n);
+                }
+                self.mode = Mode::Reading(0);
+            }
+            Err(err) => {
+                // Save the error and switch to idle mode.
+                self.cache.clear();
+                self.mode = Mode::Idle;
+                self.last_read_err = Some(err);
+            }
+        }
+    }
+
+    /// Stores the result of draining the write cache.
+    fn finish_drain(&mut self, res: io::Result<()>) {
+        match res {
+            Ok(_) => {
+                // Switch to idle mode.
+                self.cache.clear();
+                self.mode = Mode::Idle;
+            }
+            Err(err) => {
+                // Save the error.
+                self.last_write_err = Some(err);
+            }
+        };
+    }
+
+    /// Drives the operation in flight on io_uring until it finishes.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    fn poll_submitted(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        while let Some(submitted) = self.submitted {
+            let ops = self.ops.as_ref().unwrap();
+
+            match submitted {
+                Submitted::Read => {
+                    let res = futures_core::ready!(ops.poll_read(cx, &mut self.cache));
+                    self.submitted = None;
+                    self.finish_read(res);
+                }
+                Submitted::Write(written) => {
+                    let res = futures_core::ready!(ops.poll_write(cx, &self.cache[written..]));
+                    let res = match res {
+                        Ok(0) => Err(io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        )),
+                        Ok(n) if written + n < self.cache.len() => {
+                            self.submitted = Some(Submitted::Write(written + n));
+                            continue;
+                        }
+                        Ok(_) => Ok(()),
+                        Err(err) => Err(err),
+                    };
+                    self.submitted = None;
+                    self.finish_drain(res);
+                }
+            }
+        }
+        Poll::Ready(())
+    }
+
+    /// Drives the operation in flight on io_uring until it finishes.
+    #[cfg(not(all(target_os = "linux", feature = "io-uring")))]
+    fn poll_submitted(&mut self, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
 /// Modes a file can be in.
 ///
 /// The file can either be in idle mode, reading mode, or writing mode.
@@ -577,6 +666,17 @@ enum Mode {
     Writing,
 }
 
+/// An operation on io_uring that has been submitted and not finished yet.
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+#[derive(Clone, Copy)]
+enum Submitted {
+    /// A read into the cache.
+    Read,
+
+    /// A write of the cache, with the number of bytes written so far.
+    Write(usize),
+}
+
 /// The current state of a file.
 ///
 /// The `File` struct protects this state behind a lock.
@@ -587,6 +687,17 @@ struct State {
     /// The inner file.
     file: Arc<std::fs::File>,
 
+    /// Reads and writes submitted to io_uring, if the kernel supports it.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    ops: Option<Ops>,
+
+    /// The operation in flight on io_uring, if any.
+    ///
+    /// The operation is driven by whichever task holds the lock, and finished before the state is
+    /// used for anything else.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    submitted: Option<Submitted>,
+
     /// The current mode (idle, reading, or writing).
     mode: Mode,
 
@@ -611,6 +722,9 @@ struct State {
 impl LockGuard<State> {
     /// Seeks to a new position in the file.
     fn poll_seek(mut self, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<io::Result<u64>> {
+        // Finish the operation in flight, which may move the cursor.
+        futures_core::ready!(self.poll_submitted(cx));
+
         // If this operation doesn't move the cursor, then poll the current position inside the
         // file. This call should not block because it doesn't touch the actual file on disk.
         if pos == SeekFrom::Current(0) {
@@ -656,6 +770,9 @@ impl LockGuard<State> {
 
     /// Reads some bytes from the file into a buffer.
     fn poll_read(mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        // Finish the operation in flight, which may fill the cache.
+        futures_core::ready!(self.poll_submitted(cx));
+
         // If an async operation has left a read error, return it now.
         if let Some(err) = self.last_read_err.take() {
             return Poll::Ready(Err(err));
@@ -696,6 +813,17 @@ impl LockGuard<State> {
             self.cache.set_len(buf.len());
         }
 
+        // Read through io_uring if it is supported. The read wakes up the current task once it
+        // completes, so there is no need to register interest in the file lock.
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        {
+            if self.ops.is_some() {
+                self.submitted = Some(Submitted::Read);
+                futures_core::ready!(self.poll_submitted(cx));
+                return self.poll_read(cx, buf);
+            }
+        }
+
         // Register current task's interest in the file lock.
         self.register(cx);
 
@@ -706,22 +834,7 @@ impl LockGuard<State> {
                 let State { file, cache, .. } = &mut *self;
                 (&**file).read(cache)
             };
-
-            match res {
-                Ok(n) => {
-                    // Update cache length and switch to reading mode, starting from index 0.
-                    unsafe {
-                        self.cache.set_len(n);
-                    }
-                    self.mode = Mode::Reading(0);
-                }
-                Err(err) => {
-                    // Save the error and switch to idle mode.
-                    self.cache.clear();
-                    self.mode = Mode::Idle;
-                    self.last_read_err = Some(err);
-                }
-            }
+            self.finish_read(res);
         });
 
         Poll::Pending
@@ -731,7 +844,10 @@ impl LockGuard<State> {
     ///
     /// This method will also move the internal file's cursor backwards by the number of unconsumed
     /// bytes in the read cache.
-    fn poll_unread(mut self, _: &mut Context<'_>) -> Poll<io::Result<Self>> {
+    fn poll_unread(mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self>> {
+        // Finish the operation in flight, which may fill the cache.
+        futures_core::ready!(self.poll_submitted(cx));
+
         match self.mode {
             Mode::Idle | Mode::Writing => Poll::Ready(Ok(self)),
             Mode::Reading(start) => {
@@ -758,6 +874,9 @@ impl LockGuard<State> {
 
     /// Writes some data from a buffer into the file.
     fn poll_write(mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        // Finish the operation in flight, which may drain the cache.
+        futures_core::ready!(self.poll_submitted(cx));
+
         // If an async operation has left a write error, return it now.
         if let Some(err) = self.last_write_err.take() {
             return Poll::Ready(Err(err));
@@ -792,14 +911,17 @@ impl LockGuard<State> {
             self.mode = Mode::Writing;
             Poll::Ready(Ok(n))
         } else {
-            // Drain the write cache because it's full.
-            futures_core::ready!(self.poll_drain(cx))?;
-            Poll::Pending
+            // Drain the write cache because it's full, and then try again.
+            self = futures_core::ready!(self.poll_drain(cx))?;
+            self.poll_write(cx, buf)
         }
     }
 
     /// Drains the write cache.
     fn poll_drain(mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self>> {
+        // Finish the operation in flight, which may drain the cache.
+        futures_core::ready!(self.poll_submitted(cx));
+
         // If an async operation has left a write error, return it now.
         if let Some(err) = self.last_write_err.take() {
             return Poll::Ready(Err(err));
@@ -808,22 +930,24 @@ impl LockGuard<State> {
         match self.mode {
             Mode::Idle | Mode::Reading(..) => Poll::Ready(Ok(self)),
             Mode::Writing => {
+                // Write through io_uring if it is supported. The write wakes up the current task
+                // once it completes, so there is no need to register interest in the file lock.
+                #[cfg(all(target_os = "linux", feature = "io-uring"))]
+                {
+                    if self.ops.is_some() {
+                        self.submitted = Some(Submitted::Write(0));
+                        futures_core::ready!(self.poll_submitted(cx));
+                        return self.poll_drain(cx);
+                    }
+                }
+
                 // Register current task's interest in the file lock.
                 self.register(cx);
 
                 // Start a write operation asynchronously.
                 spawn_blocking(move || {
-                    match (&*self.file).write_all(&self.cache) {
-                        Ok(_) => {
-                            // Switch to idle mode.
-                            self.cache.clear();
-                            self.mode = Mode::Idle;
-                        }
-                        Err(err) => {
-                            // Save the error.
-                            self.last_write_err = Some(err);
-                        }
-                    };
+                    let res = (&*self.file).write_all(&self.cache);
+                    self.finish_drain(res);
                 });
 
                 Poll::Pending
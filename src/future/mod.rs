@@ -66,6 +66,10 @@ cfg_default! {
     mod timeout;
 }
 
+cfg_unstable_default! {
+    pub use timeout::timeout_at;
+}
+
 cfg_unstable! {
     pub use into_future::IntoFuture;
     pub(crate) use maybe_done::MaybeDone;
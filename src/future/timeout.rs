@@ -2,12 +2,14 @@ use std::error::Error;
 use std::fmt;
 use std::pin::Pin;
 use std::time::Duration;
+#[cfg(feature = "unstable")]
+use std::time::Instant;
 use std::future::Future;
 
-use futures_timer::Delay;
 use pin_project_lite::pin_project;
 
 use crate::task::{Context, Poll};
+use crate::timer::Timer;
 
 /// Awaits a future or times out after a duration of time.
 ///
@@ -35,7 +37,39 @@ where
 {
     let f = TimeoutFuture {
         future: f,
-        delay: Delay::new(dur),
+        delay: Timer::new(dur),
+    };
+    f.await
+}
+
+/// Awaits a future or times out at a point in time.
+///
+/// If the deadline has already passed, the future is still polled once before timing out.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+/// #
+/// use std::time::{Duration, Instant};
+///
+/// use async_std::future;
+///
+/// let deadline = Instant::now() + Duration::from_millis(5);
+/// assert!(future::timeout_at(deadline, future::pending::<()>()).await.is_err());
+/// assert_eq!(future::timeout_at(deadline, future::ready(1)).await, Ok(1));
+/// #
+/// # Ok(()) }) }
+/// ```
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub async fn timeout_at<F, T>(deadline: Instant, f: F) -> Result<T, TimeoutError>
+where
+    F: Future<Output = T>,
+{
+    let f = TimeoutFuture {
+        future: f,
+        delay: Timer::at(deadline),
     };
     f.await
 }
@@ -46,14 +80,14 @@ pin_project! {
         #[pin]
         future: F,
         #[pin]
-        delay: Delay,
+        delay: Timer,
     }
 }
 
 impl<F> TimeoutFuture<F> {
     #[allow(dead_code)]
     pub(super) fn new(future: F, dur: Duration) -> TimeoutFuture<F> {
-        TimeoutFuture { future: future, delay: Delay::new(dur) }
+        TimeoutFuture { future: future, delay: Timer::new(dur) }
     }
 }
 
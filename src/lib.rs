@@ -211,6 +211,18 @@
 //! features = ["attributes"]
 //! ```
 //!
+//! On Linux, the `io-uring` Cargo feature submits reads and writes on TCP streams, UDP sockets,
+//! Unix streams, and files to an io_uring instance instead of running them as separate system
+//! calls, and instead of running file operations on a thread pool. Operations queued while the
+//! reactor is busy are submitted together. The APIs stay the same, and kernels without io_uring
+//! support fall back to the default behavior:
+//!
+//! ```toml
+//! [dependencies.async-std]
+//! version = "1.0.0"
+//! features = ["io-uring"]
+//! ```
+//!
 //! Additionally it's possible to only use the core traits and combinators by
 //! only enabling the `std` Cargo feature:
 //!
@@ -253,6 +265,9 @@ pub use async_attributes::{main, test};
 #[cfg(feature = "std")]
 mod macros;
 
+#[cfg(any(feature = "unstable", feature = "default"))]
+mod timer;
+
 cfg_alloc! {
     pub mod task;
     pub mod future;
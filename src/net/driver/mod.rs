@@ -1,25 +1,116 @@
+use std::cell::{Cell, RefCell};
 use std::fmt;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
 
+use crossbeam_channel::{Receiver, Sender};
 use mio::{self, Evented};
 use once_cell::sync::Lazy;
-use slab::Slab;
 
 use crate::io;
-use crate::task::{Context, Poll, Waker};
+use crate::task::executor;
+use crate::task::{budget, Context, Poll, Waker};
+use crate::timer;
 use crate::utils::abort_on_panic;
 
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+pub(crate) mod uring;
+
 /// Data associated with a registered I/O handle.
 #[derive(Debug)]
 struct Entry {
-    /// A unique identifier.
-    token: mio::Token,
+    /// The index of the shard the I/O handle is registered in.
+    shard: usize,
+
+    /// The readiness events the I/O handle is registered for.
+    interest: mio::Ready,
+
+    /// The registration mode of the I/O handle.
+    opts: mio::PollOpt,
 
     /// Tasks that are blocked on reading from this I/O handle.
     readers: Mutex<Readers>,
 
     /// Thasks that are blocked on writing to this I/O handle.
     writers: Mutex<Writers>,
+
+    /// Tasks that are blocked on priority data from this I/O handle.
+    urgent: Mutex<Readers>,
+}
+
+impl Entry {
+    /// Returns the token of the I/O handle, which is the address of the entry.
+    fn token(&self) -> mio::Token {
+        mio::Token(self as *const Entry as usize)
+    }
+
+    /// Runs a non-blocking read operation, registering the task if the operation would block.
+    fn poll_read_with<F, R>(&self, cx: &mut Context<'_>, mut f: F) -> Poll<io::Result<R>>
+    where
+        F: FnMut() -> io::Result<R>,
+    {
+        // Yield if the task has used up its budget.
+        futures_core::ready!(budget::poll_proceed(cx));
+
+        // If the operation isn't blocked, return its result.
+        match f() {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            res => return Poll::Ready(res),
+        }
+
+        // Lock the waker list.
+        let mut readers = self.readers.lock().unwrap();
+
+        // Try running the operation again.
+        match f() {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            res => return Poll::Ready(res),
+        }
+
+        // Register the task if it isn't registered already.
+        if readers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+            readers.wakers.push(cx.waker().clone());
+        }
+
+        readers.ready = false;
+
+        Poll::Pending
+    }
+
+    /// Runs a non-blocking write operation, registering the task if the operation would block.
+    fn poll_write_with<F, R>(&self, cx: &mut Context<'_>, mut f: F) -> Poll<io::Result<R>>
+    where
+        F: FnMut() -> io::Result<R>,
+    {
+        // Yield if the task has used up its budget.
+        futures_core::ready!(budget::poll_proceed(cx));
+
+        // If the operation isn't blocked, return its result.
+        match f() {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            res => return Poll::Ready(res),
+        }
+
+        // Lock the waker list.
+        let mut writers = self.writers.lock().unwrap();
+
+        // Try running the operation again.
+        match f() {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            res => return Poll::Ready(res),
+        }
+
+        // Register the task if it isn't registered already.
+        if writers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+            writers.wakers.push(cx.waker().clone());
+        }
+
+        writers.ready = false;
+
+        Poll::Pending
+    }
 }
 
 /// The set of `Waker`s interested in read readiness.
@@ -42,149 +133,486 @@ struct Writers {
     wakers: Vec<Waker>
 }
 
+/// The token of the dummy I/O handle used to wake up a shard's poller.
+const NOTIFY_TOKEN: mio::Token = mio::Token(0);
+
+/// The token of the io_uring completion queue, registered in the first shard.
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+const RING_TOKEN: mio::Token = mio::Token(1);
+
 /// The state of a networking driver.
+///
+/// I/O handles are spread over several shards, each with its own poller and driver thread. The
+/// first shard also drives timers.
 struct Reactor {
-    /// A mio instance that polls for new events.
-    poller: mio::Poll,
+    /// The shards I/O handles are registered in.
+    shards: Vec<Shard>,
 
-    /// A collection of registered I/O handles.
-    entries: Mutex<Slab<Arc<Entry>>>,
+    /// The shard assigned to the next thread that registers an I/O handle.
+    next_shard: AtomicUsize,
 
-    /// Dummy I/O handle that is only used to wake up the polling thread.
-    notify_reg: (mio::Registration, mio::SetReadiness),
+    /// Set to `true` when the polling threads should stop.
+    closed: AtomicBool,
 
-    /// An identifier for the notification handle.
-    notify_token: mio::Token,
+    /// The io_uring instance reads and writes are submitted to, if the kernel supports it.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    ring: Option<uring::Ring>,
 }
 
 impl Reactor {
-    /// Creates a new reactor for polling I/O events.
-    fn new() -> io::Result<Reactor> {
-        let poller = mio::Poll::new()?;
-        let notify_reg = mio::Registration::new2();
-
-        let mut reactor = Reactor {
-            poller,
-            entries: Mutex::new(Slab::new()),
-            notify_reg,
-            notify_token: mio::Token(0),
+    /// Creates a new reactor with the given number of shards.
+    fn new(num_shards: usize) -> io::Result<Reactor> {
+        let reactor = Reactor {
+            shards: (0..num_shards).map(Shard::new).collect::<io::Result<_>>()?,
+            next_shard: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+            #[cfg(all(target_os = "linux", feature = "io-uring"))]
+            ring: uring::Ring::new().ok(),
         };
 
-        // Register a dummy I/O handle for waking up the polling thread.
-        let entry = reactor.register(&reactor.notify_reg.0)?;
-        reactor.notify_token = entry.token;
+        // Register the io_uring file descriptor, which becomes readable on completions.
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        {
+            use std::os::unix::io::AsRawFd;
+
+            if let Some(ring) = &reactor.ring {
+                reactor.shards[0].poller.register(
+                    &mio::unix::EventedFd(&ring.as_raw_fd()),
+                    RING_TOKEN,
+                    mio::Ready::readable(),
+                    mio::PollOpt::edge(),
+                )?;
+            }
+        }
 
         Ok(reactor)
     }
 
-    /// Registers an I/O event source and returns its associated entry.
-    fn register(&self, source: &dyn Evented) -> io::Result<Arc<Entry>> {
-        let mut entries = self.entries.lock().unwrap();
+    /// Returns the shard I/O handles registered on the current thread are assigned to.
+    ///
+    /// Threads are assigned to shards in a round-robin fashion, so each executor thread polls its
+    /// own shard when it is idle.
+    fn current_shard(&self) -> &Shard {
+        thread_local! {
+            static SHARD: Cell<Option<usize>> = Cell::new(None);
+        }
 
-        // Reserve a vacant spot in the slab and use its key as the token value.
-        let vacant = entries.vacant_entry();
-        let token = mio::Token(vacant.key());
+        let index = SHARD.with(|shard| match shard.get() {
+            Some(index) => index,
+            None => {
+                let index = self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+                shard.set(Some(index));
+                index
+            }
+        });
+        &self.shards[index]
+    }
 
-        // Allocate an entry and insert it into the slab.
+    /// Registers an I/O event source and returns its associated entry.
+    ///
+    /// The source is registered for the given readiness events in the given mode. Oneshot
+    /// registrations in level-triggered mode are re-armed whenever a task waits on the source.
+    fn register(
+        &self,
+        source: &dyn Evented,
+        interest: mio::Ready,
+        opts: mio::PollOpt,
+    ) -> io::Result<Arc<Entry>> {
+        let shard = self.current_shard();
+
+        // Allocate an entry and use its address as the token value.
         let entry = Arc::new(Entry {
-            token,
+            shard: shard.index,
+            interest,
+            opts,
             readers: Mutex::new(Readers { ready: false, wakers: Vec::new() }),
             writers: Mutex::new(Writers { ready: false, wakers: Vec::new() }),
+            urgent: Mutex::new(Readers { ready: false, wakers: Vec::new() }),
         });
-        vacant.insert(entry.clone());
 
-        // Register the I/O event source in the poller.
-        let interest = mio::Ready::all();
-        let opts = mio::PollOpt::edge();
-        self.poller.register(source, token, interest, opts)?;
+        // Register the I/O event source in the poller, which keeps the entry alive until the
+        // source is deregistered.
+        shard
+            .poller
+            .register(source, entry.token(), interest, opts)?;
+        let _ = Arc::into_raw(entry.clone());
 
         Ok(entry)
     }
 
     /// Deregisters an I/O event source associated with an entry.
     fn deregister(&self, source: &dyn Evented, entry: &Entry) -> io::Result<()> {
+        let shard = &self.shards[entry.shard];
+
         // Deregister the I/O object from the mio instance.
-        self.poller.deregister(source)?;
+        shard.poller.deregister(source)?;
 
-        // Remove the entry associated with the I/O object.
-        self.entries.lock().unwrap().remove(entry.token.0);
+        // Drop the tasks blocked on the I/O handle, which won't receive events anymore.
+        entry.readers.lock().unwrap().wakers.clear();
+        entry.writers.lock().unwrap().wakers.clear();
+        entry.urgent.lock().unwrap().wakers.clear();
+
+        // Events that have already been received may still refer to the entry, so let the shard
+        // release it after processing them.
+        let entry = unsafe { Arc::from_raw(entry as *const Entry) };
+        shard.released.0.send(entry).unwrap();
 
         Ok(())
     }
 
-    // fn notify(&self) {
-    //     self.notify_reg
-    //         .1
-    //         .set_readiness(mio::Ready::readable())
-    //         .unwrap();
-    // }
+    /// Wakes up the thread polling the first shard, which drives timers.
+    fn notify(&self) {
+        self.shards[0].notify();
+    }
 }
 
-/// The state of the global networking driver.
-static REACTOR: Lazy<Reactor> = Lazy::new(|| {
-    // Spawn a thread that waits on the poller for new events and wakes up tasks blocked on I/O
-    // handles.
-    std::thread::Builder::new()
-        .name("async-std/net".to_string())
-        .spawn(move || {
-            // If the driver thread panics, there's not much we can do. It is not a
-            // recoverable error and there is no place to propagate it into so we just abort.
-            abort_on_panic(|| {
-                main_loop().expect("async networking thread has panicked");
-            })
+/// A poller along with the I/O handles registered in it.
+///
+/// The token of an I/O handle is the address of its entry, so registering a handle doesn't need a
+/// lookup table or any locks.
+///
+/// The shard is polled by its driver thread, unless an idle executor thread polls it instead.
+/// Only one thread polls a shard at a time.
+struct Shard {
+    /// The position of the shard in the reactor.
+    index: usize,
+
+    /// A mio instance that polls for new events.
+    poller: mio::Poll,
+
+    /// Dummy I/O handle that is only used to wake up the polling thread.
+    notify_reg: (mio::Registration, mio::SetReadiness),
+
+    /// The threads polling the shard.
+    pollers: Mutex<Pollers>,
+
+    /// Notified when a thread stops polling the shard.
+    pollers_changed: Condvar,
+
+    /// Set to `true` when the executor thread parked on the shard should wake up.
+    unparked: AtomicBool,
+
+    /// Entries of deregistered I/O handles, released once the current events are processed.
+    released: (Sender<Arc<Entry>>, Receiver<Arc<Entry>>),
+}
+
+/// The threads polling a shard.
+struct Pollers {
+    /// Set to `true` while the driver thread polls the shard.
+    driver: bool,
+
+    /// Set to `true` while an idle executor thread polls the shard or waits to do so.
+    worker: bool,
+}
+
+impl Shard {
+    /// Creates a new shard.
+    fn new(index: usize) -> io::Result<Shard> {
+        let poller = mio::Poll::new()?;
+        let notify_reg = mio::Registration::new2();
+
+        // Register a dummy I/O handle for waking up the polling thread.
+        poller.register(
+            &notify_reg.0,
+            NOTIFY_TOKEN,
+            mio::Ready::readable(),
+            mio::PollOpt::edge(),
+        )?;
+
+        Ok(Shard {
+            index,
+            poller,
+            notify_reg,
+            pollers: Mutex::new(Pollers {
+                driver: false,
+                worker: false,
+            }),
+            pollers_changed: Condvar::new(),
+            unparked: AtomicBool::new(false),
+            released: crossbeam_channel::unbounded(),
         })
-        .expect("cannot start a thread driving blocking tasks");
+    }
 
-    Reactor::new().expect("cannot initialize reactor")
-});
+    /// Wakes up the polling thread.
+    fn notify(&self) {
+        self.notify_reg
+            .1
+            .set_readiness(mio::Ready::readable())
+            .unwrap();
+    }
 
-/// Waits on the poller for new events and wakes up tasks blocked on I/O handles.
-fn main_loop() -> io::Result<()> {
-    let reactor = &REACTOR;
-    let mut events = mio::Events::with_capacity(1000);
+    /// Waits on the poller for new events and wakes up tasks blocked on I/O handles or timers.
+    ///
+    /// If `parked` is `true`, the poller isn't blocked on when timers wake up tasks, since they may
+    /// be scheduled on the current thread.
+    fn poll(&self, events: &mut mio::Events, parked: bool) -> io::Result<()> {
+        // Fire expired timers, and find out how long until the next one expires.
+        let timeout = if self.index == 0 {
+            match timer::process() {
+                (fired, _) if fired > 0 && parked => Some(Duration::from_secs(0)),
+                (_, timeout) => timeout,
+            }
+        } else {
+            None
+        };
 
-    loop {
-        // Block on the poller until at least one new event comes in.
-        reactor.poller.poll(&mut events, None)?;
+        // Submit reads and writes queued on io_uring since the last turn, which may wake up tasks
+        // if they complete right away.
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        let timeout = match REACTOR.ring.as_ref().map(|ring| ring.flush()) {
+            Some(true) if parked => Some(Duration::from_secs(0)),
+            _ => timeout,
+        };
 
-        // Lock the entire entry table while we're processing new events.
-        let entries = reactor.entries.lock().unwrap();
+        // Block on the poller until at least one new event comes in or the next timer expires.
+        self.poller.poll(events, timeout)?;
 
         for event in events.iter() {
             let token = event.token();
 
-            if token == reactor.notify_token {
+            // If this is the io_uring token, process completed operations.
+            #[cfg(all(target_os = "linux", feature = "io-uring"))]
+            {
+                if token == RING_TOKEN {
+                    if let Some(ring) = &REACTOR.ring {
+                        ring.complete();
+                    }
+                    continue;
+                }
+            }
+
+            if token == NOTIFY_TOKEN {
                 // If this is the notification token, we just need the notification state.
-                reactor.notify_reg.1.set_readiness(mio::Ready::empty())?;
+                self.notify_reg.1.set_readiness(mio::Ready::empty())?;
             } else {
-                // Otherwise, look for the entry associated with this token.
-                if let Some(entry) = entries.get(token.0) {
-                    // Set the readiness flags from this I/O event.
-                    let readiness = event.readiness();
-
-                    // Wake up reader tasks blocked on this I/O handle.
-                    if !(readiness & reader_interests()).is_empty() {
-                        let mut readers = entry.readers.lock().unwrap();
-                        readers.ready = true;
-                        for w in readers.wakers.drain(..) {
-                            w.wake();
-                        }
+                // Otherwise, the token is the address of the entry, which stays alive until the
+                // events are processed.
+                let entry = unsafe { &*(token.0 as *const Entry) };
+
+                // Set the readiness flags from this I/O event.
+                let readiness = event.readiness();
+
+                // A oneshot registration is disarmed after this event, so wake up all tasks
+                // blocked on this I/O handle to let them re-arm it if they keep waiting.
+                if entry.opts.is_oneshot() {
+                    let mut wakers = Vec::new();
+                    wakers.append(&mut entry.readers.lock().unwrap().wakers);
+                    wakers.append(&mut entry.writers.lock().unwrap().wakers);
+                    wakers.append(&mut entry.urgent.lock().unwrap().wakers);
+                    for w in wakers {
+                        w.wake();
+                    }
+                }
+
+                // Wake up reader tasks blocked on this I/O handle.
+                if !(readiness & reader_interests()).is_empty() {
+                    let mut readers = entry.readers.lock().unwrap();
+                    readers.ready = true;
+                    for w in readers.wakers.drain(..) {
+                        w.wake();
+                    }
+                }
+
+                // Wake up writer tasks blocked on this I/O handle.
+                if !(readiness & writer_interests()).is_empty() {
+                    let mut writers = entry.writers.lock().unwrap();
+                    writers.ready = true;
+                    for w in writers.wakers.drain(..) {
+                        w.wake();
                     }
+                }
 
-                    // Wake up writer tasks blocked on this I/O handle.
-                    if !(readiness & writer_interests()).is_empty() {
-                        let mut writers = entry.writers.lock().unwrap();
-                        writers.ready = true;
-                        for w in writers.wakers.drain(..) {
-                            w.wake();
-                        }
+                // Wake up tasks blocked on priority data from this I/O handle.
+                if !(readiness & priority()).is_empty() {
+                    let mut urgent = entry.urgent.lock().unwrap();
+                    urgent.ready = true;
+                    for w in urgent.wakers.drain(..) {
+                        w.wake();
                     }
                 }
             }
         }
+
+        // No events refer to deregistered I/O handles anymore, so their entries can be released.
+        while let Ok(entry) = self.released.1.try_recv() {
+            drop(entry);
+        }
+
+        Ok(())
+    }
+}
+
+/// The threads driving the global networking driver.
+static THREADS: Lazy<Mutex<Vec<thread::JoinHandle<()>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Set to `true` once the global networking driver has been started.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// The state of the global networking driver.
+static REACTOR: Lazy<Reactor> = Lazy::new(|| {
+    let config = executor::config();
+    let num_shards = config.reactor_shards;
+    let reactor = Reactor::new(num_shards).expect("cannot initialize reactor");
+
+    // Spawn threads that wait on the pollers for new events and wake up tasks blocked on I/O
+    // handles.
+    let mut threads = THREADS.lock().unwrap();
+    for index in 0..num_shards {
+        let handle = thread::Builder::new()
+            .name("async-std/net".to_string())
+            .spawn(move || {
+                // If the driver thread panics, there's not much we can do. It is not a
+                // recoverable error and there is no place to propagate it into so we just abort.
+                abort_on_panic(|| {
+                    main_loop(&REACTOR.shards[index])
+                        .expect("async networking thread has panicked");
+                })
+            })
+            .expect("cannot start a thread driving blocking tasks");
+        threads.push(handle);
+    }
+    STARTED.store(true, Ordering::SeqCst);
+
+    reactor
+});
+
+/// Wakes up the thread driving timers, starting the networking driver if needed.
+///
+/// This is used by timers to make the thread pick a new poll timeout.
+pub(crate) fn notify() {
+    REACTOR.notify();
+}
+
+/// Stops the threads driving the networking driver, if it has been started.
+///
+/// I/O handles stop receiving readiness events and timers stop firing afterwards.
+#[cfg(feature = "unstable")]
+pub(crate) fn shutdown() {
+    let handles: Vec<_> = THREADS.lock().unwrap().drain(..).collect();
+    if handles.is_empty() {
+        return;
+    }
+
+    REACTOR.closed.store(true, Ordering::SeqCst);
+    for shard in &REACTOR.shards {
+        let _pollers = shard.pollers.lock().unwrap();
+        shard.pollers_changed.notify_all();
+        shard.notify();
+    }
+    for handle in handles {
+        let _ = handle.join();
     }
 }
 
+/// Polls a shard until the networking driver is stopped.
+///
+/// The thread steps aside whenever an idle executor thread polls the shard instead.
+fn main_loop(shard: &Shard) -> io::Result<()> {
+    let mut events = mio::Events::with_capacity(1000);
+
+    loop {
+        let mut pollers = shard.pollers.lock().unwrap();
+        while pollers.worker && !REACTOR.closed.load(Ordering::SeqCst) {
+            pollers = shard.pollers_changed.wait(pollers).unwrap();
+        }
+        if REACTOR.closed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        pollers.driver = true;
+        drop(pollers);
+
+        let res = shard.poll(&mut events, false);
+
+        let mut pollers = shard.pollers.lock().unwrap();
+        pollers.driver = false;
+        shard.pollers_changed.notify_all();
+        drop(pollers);
+
+        res?;
+    }
+}
+
+/// An idle executor thread's claim on polling its shard.
+///
+/// While the claim is held, the shard's driver thread doesn't poll it.
+pub(crate) struct Parker {
+    shard: &'static Shard,
+}
+
+impl Parker {
+    /// Claims the shard of the current thread, so that it can poll for I/O while it has no tasks
+    /// to run.
+    ///
+    /// Returns `None` if the networking driver hasn't been started or if another thread has
+    /// already claimed the shard.
+    pub(crate) fn new() -> Option<Parker> {
+        if !STARTED.load(Ordering::SeqCst) || REACTOR.closed.load(Ordering::SeqCst) {
+            return None;
+        }
+        let shard = REACTOR.current_shard();
+
+        let mut pollers = shard.pollers.lock().unwrap();
+        if pollers.worker {
+            return None;
+        }
+        pollers.worker = true;
+
+        // Interrupt the driver thread so that it steps aside.
+        if pollers.driver {
+            shard.notify();
+        }
+
+        Some(Parker { shard })
+    }
+
+    /// Returns the index of the claimed shard, which is passed to `unpark`.
+    pub(crate) fn shard(&self) -> usize {
+        self.shard.index
+    }
+
+    /// Blocks the current thread on the shard's poller until an I/O event comes in, a timer
+    /// fires, or the thread is unparked.
+    ///
+    /// Tasks woken up by the events are scheduled on the current thread.
+    pub(crate) fn park(&self) {
+        thread_local! {
+            static EVENTS: RefCell<mio::Events> = RefCell::new(mio::Events::with_capacity(1000));
+        }
+
+        // Wait until the driver thread steps aside.
+        let mut pollers = self.shard.pollers.lock().unwrap();
+        while pollers.driver {
+            pollers = self.shard.pollers_changed.wait(pollers).unwrap();
+        }
+        drop(pollers);
+
+        // The driver thread may have consumed the notification meant for this thread.
+        if self.shard.unparked.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        // Errors are left to the driver thread, which hits them too once it takes over.
+        EVENTS.with(|events| {
+            let _ = self.shard.poll(&mut events.borrow_mut(), true);
+        });
+    }
+}
+
+impl Drop for Parker {
+    fn drop(&mut self) {
+        let mut pollers = self.shard.pollers.lock().unwrap();
+        pollers.worker = false;
+        self.shard.pollers_changed.notify_all();
+    }
+}
+
+/// Wakes up an executor thread parked on a shard.
+pub(crate) fn unpark(shard: usize) {
+    let shard = &REACTOR.shards[shard];
+    shard.unparked.store(true, Ordering::SeqCst);
+    shard.notify();
+}
+
 /// An I/O handle powered by the networking driver.
 ///
 /// This handle wraps an I/O event source and exposes a "futurized" interface on top of it,
@@ -193,6 +621,10 @@ pub struct Watcher<T: Evented> {
     /// Data associated with the I/O handle.
     entry: Arc<Entry>,
 
+    /// Reads and writes submitted to io_uring, if the handle uses it.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    ops: Option<uring::Ops>,
+
     /// The I/O event source.
     source: Option<T>,
 }
@@ -203,12 +635,97 @@ impl<T: Evented> Watcher<T> {
     /// The provided I/O event source will be kept registered inside the reactor's poller for the
     /// lifetime of the returned I/O handle.
     pub fn new(source: T) -> Watcher<T> {
-        Watcher {
-            entry: REACTOR
-                .register(&source)
-                .expect("cannot register an I/O event source"),
+        Watcher::try_new(source).expect("cannot register an I/O event source")
+    }
+
+    /// Creates a new I/O handle, returning an error if the source cannot be registered.
+    pub fn try_new(source: T) -> io::Result<Watcher<T>> {
+        Watcher::try_with_interest(source, mio::Ready::all(), mio::PollOpt::edge())
+    }
+
+    /// Creates a new I/O handle registered for the given readiness events in the given mode.
+    ///
+    /// Oneshot registrations in level-triggered mode are re-armed whenever a task starts waiting
+    /// on the handle. Other oneshot registrations need to be re-armed with `rearm`.
+    pub fn try_with_interest(
+        source: T,
+        interest: mio::Ready,
+        opts: mio::PollOpt,
+    ) -> io::Result<Watcher<T>> {
+        Ok(Watcher {
+            entry: REACTOR.register(&source, interest, opts)?,
+            #[cfg(all(target_os = "linux", feature = "io-uring"))]
+            ops: None,
             source: Some(source),
+        })
+    }
+
+    /// Re-arms a oneshot registration so that it delivers another readiness event.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn rearm(&self) -> io::Result<()> {
+        REACTOR.shards[self.entry.shard].poller.reregister(
+            self.source.as_ref().unwrap(),
+            self.entry.token(),
+            self.entry.interest,
+            self.entry.opts,
+        )
+    }
+
+    /// Re-arms the registration if a task has started waiting and the mode requires it.
+    fn rearm_if_pending<R>(&self, poll: Poll<io::Result<R>>) -> Poll<io::Result<R>> {
+        let opts = self.entry.opts;
+        if poll.is_pending() && opts.is_oneshot() && opts.is_level() {
+            if let Err(err) = self.rearm() {
+                return Poll::Ready(Err(err));
+            }
+        }
+        poll
+    }
+
+    /// Reads some bytes into `buf`.
+    ///
+    /// The read is submitted to io_uring once the handle is readable if the handle uses it.
+    /// Otherwise, `read` is run whenever the handle is readable, until it doesn't fail with
+    /// `WouldBlock`.
+    pub fn poll_read_buf<F>(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        mut read: F,
+    ) -> Poll<io::Result<usize>>
+    where
+        F: FnMut(&T, &mut [u8]) -> io::Result<usize>,
+    {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        {
+            if let Some(ops) = &self.ops {
+                return ops.poll_read(cx, buf);
+            }
+        }
+        self.poll_read_with(cx, |source| read(source, buf))
+    }
+
+    /// Writes some bytes from `buf`.
+    ///
+    /// The write is submitted to io_uring once the handle is writable if the handle uses it.
+    /// Otherwise, `write` is run whenever the handle is writable, until it doesn't fail with
+    /// `WouldBlock`.
+    pub fn poll_write_buf<F>(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        mut write: F,
+    ) -> Poll<io::Result<usize>>
+    where
+        F: FnMut(&T, &[u8]) -> io::Result<usize>,
+    {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        {
+            if let Some(ops) = &self.ops {
+                return ops.poll_write(cx, buf);
+            }
         }
+        self.poll_write_with(cx, |source| write(source, buf))
     }
 
     /// Returns a reference to the inner I/O event source.
@@ -216,6 +733,12 @@ impl<T: Evented> Watcher<T> {
         self.source.as_ref().unwrap()
     }
 
+    /// Returns a mutable reference to the inner I/O event source.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.source.as_mut().unwrap()
+    }
+
     /// Polls the inner I/O source for a non-blocking read operation.
     ///
     /// If the operation returns an error of the `io::ErrorKind::WouldBlock` kind, the current task
@@ -224,29 +747,26 @@ impl<T: Evented> Watcher<T> {
     where
         F: FnMut(&'a T) -> io::Result<R>,
     {
-        // If the operation isn't blocked, return its result.
-        match f(self.source.as_ref().unwrap()) {
-            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
-            res => return Poll::Ready(res),
-        }
-
-        // Lock the waker list.
-        let mut readers = self.entry.readers.lock().unwrap();
-
-        // Try running the operation again.
-        match f(self.source.as_ref().unwrap()) {
-            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
-            res => return Poll::Ready(res),
-        }
-
-        // Register the task if it isn't registered already.
-        if readers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
-            readers.wakers.push(cx.waker().clone());
-        }
-
-        readers.ready = false;
+        let source = self.source.as_ref().unwrap();
+        let poll = self.entry.poll_read_with(cx, || f(source));
+        self.rearm_if_pending(poll)
+    }
 
-        Poll::Pending
+    /// Polls the inner I/O source for a non-blocking read operation that needs mutable access.
+    ///
+    /// This is like `poll_read_with`, except that the operation receives a mutable reference.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn poll_read_with_mut<F, R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut f: F,
+    ) -> Poll<io::Result<R>>
+    where
+        F: FnMut(&mut T) -> io::Result<R>,
+    {
+        let source = self.source.as_mut().unwrap();
+        let poll = self.entry.poll_read_with(cx, || f(source));
+        self.rearm_if_pending(poll)
     }
 
     /// Polls the inner I/O source for a non-blocking write operation.
@@ -261,29 +781,26 @@ impl<T: Evented> Watcher<T> {
     where
         F: FnMut(&'a T) -> io::Result<R>,
     {
-        // If the operation isn't blocked, return its result.
-        match f(self.source.as_ref().unwrap()) {
-            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
-            res => return Poll::Ready(res),
-        }
-
-        // Lock the waker list.
-        let mut writers = self.entry.writers.lock().unwrap();
-
-        // Try running the operation again.
-        match f(self.source.as_ref().unwrap()) {
-            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
-            res => return Poll::Ready(res),
-        }
-
-        // Register the task if it isn't registered already.
-        if writers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
-            writers.wakers.push(cx.waker().clone());
-        }
-
-        writers.ready = false;
+        let source = self.source.as_ref().unwrap();
+        let poll = self.entry.poll_write_with(cx, || f(source));
+        self.rearm_if_pending(poll)
+    }
 
-        Poll::Pending
+    /// Polls the inner I/O source for a non-blocking write operation that needs mutable access.
+    ///
+    /// This is like `poll_write_with`, except that the operation receives a mutable reference.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn poll_write_with_mut<F, R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut f: F,
+    ) -> Poll<io::Result<R>>
+    where
+        F: FnMut(&mut T) -> io::Result<R>,
+    {
+        let source = self.source.as_mut().unwrap();
+        let poll = self.entry.poll_write_with(cx, || f(source));
+        self.rearm_if_pending(poll)
     }
 
     /// Polls the inner I/O source until a non-blocking read can be performed.
@@ -302,6 +819,8 @@ impl<T: Evented> Watcher<T> {
         if readers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
             readers.wakers.push(cx.waker().clone());
         }
+        drop(readers);
+        let _ = self.rearm_if_pending(Poll::<io::Result<()>>::Pending);
         Poll::Pending
     }
 
@@ -320,24 +839,116 @@ impl<T: Evented> Watcher<T> {
         if writers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
             writers.wakers.push(cx.waker().clone());
         }
+        drop(writers);
+        let _ = self.rearm_if_pending(Poll::<io::Result<()>>::Pending);
+        Poll::Pending
+    }
+
+    /// Polls the inner I/O source until priority data can be read.
+    ///
+    /// If no priority data is available, the `Waker` will be saved and notified when priority
+    /// data arrives.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn poll_priority_ready(&self, cx: &mut Context<'_>) -> Poll<()> {
+        // Lock the waker list.
+        let mut urgent = self.entry.urgent.lock().unwrap();
+        if urgent.ready {
+            return Poll::Ready(());
+        }
+        // Register the task if it isn't registered already.
+        if urgent.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+            urgent.wakers.push(cx.waker().clone());
+        }
+        drop(urgent);
+        let _ = self.rearm_if_pending(Poll::<io::Result<()>>::Pending);
         Poll::Pending
     }
 
+    /// Clears the read readiness, so that `poll_read_ready` waits for the next readiness event.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn clear_read_ready(&self) {
+        self.entry.readers.lock().unwrap().ready = false;
+    }
+
+    /// Clears the write readiness, so that `poll_write_ready` waits for the next readiness event.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn clear_write_ready(&self) {
+        self.entry.writers.lock().unwrap().ready = false;
+    }
+
+    /// Clears the priority data readiness, so that `poll_priority_ready` waits for new data.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn clear_priority_ready(&self) {
+        self.entry.urgent.lock().unwrap().ready = false;
+    }
+
     /// Deregisters and returns the inner I/O source.
     ///
     /// This method is typically used to convert `Watcher`s to raw file descriptors/handles.
     #[allow(dead_code)]
-    pub fn into_inner(mut self) -> T {
+    pub fn into_inner(self) -> T {
+        self.try_into_inner()
+            .expect("cannot deregister I/O event source")
+    }
+
+    /// Deregisters and returns the inner I/O source, or an error if deregistering fails.
+    ///
+    /// The I/O source is closed if deregistering fails.
+    #[cfg_attr(not(all(unix, feature = "unstable")), allow(dead_code))]
+    pub fn try_into_inner(mut self) -> io::Result<T> {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        drop(self.ops.take());
+
         let source = self.source.take().unwrap();
-        REACTOR
-            .deregister(&source, &self.entry)
-            .expect("cannot deregister I/O event source");
-        source
+        REACTOR.deregister(&source, &self.entry)?;
+        Ok(source)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+impl<T: Evented + std::os::unix::io::AsRawFd> Watcher<T> {
+    /// Creates a new I/O handle for a byte stream.
+    ///
+    /// Reads and writes through `poll_read_buf` and `poll_write_buf` are submitted to io_uring if
+    /// the kernel supports it.
+    pub fn new_stream(source: T) -> Watcher<T> {
+        let mut watcher = Watcher::new(source);
+        let fd = watcher.get_ref().as_raw_fd();
+        watcher.ops = uring::Ops::socket(fd, watcher.entry.clone(), false);
+        watcher
+    }
+
+    /// Creates a new I/O handle for a datagram socket.
+    ///
+    /// Reads and writes through `poll_read_buf` and `poll_write_buf` are submitted to io_uring if
+    /// the kernel supports it.
+    pub fn new_datagram(source: T) -> Watcher<T> {
+        let mut watcher = Watcher::new(source);
+        let fd = watcher.get_ref().as_raw_fd();
+        watcher.ops = uring::Ops::socket(fd, watcher.entry.clone(), true);
+        watcher
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "io-uring")))]
+impl<T: Evented> Watcher<T> {
+    /// Creates a new I/O handle for a byte stream.
+    pub fn new_stream(source: T) -> Watcher<T> {
+        Watcher::new(source)
+    }
+
+    /// Creates a new I/O handle for a datagram socket.
+    pub fn new_datagram(source: T) -> Watcher<T> {
+        Watcher::new(source)
     }
 }
 
 impl<T: Evented> Drop for Watcher<T> {
     fn drop(&mut self) {
+        // Cancel operations on the source before it gets closed.
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        drop(self.ops.take());
+
         if let Some(ref source) = self.source {
             REACTOR
                 .deregister(source, &self.entry)
@@ -367,6 +978,18 @@ fn writer_interests() -> mio::Ready {
     mio::Ready::writable() | hup()
 }
 
+/// Returns a flag containing the priority data status.
+#[inline]
+fn priority() -> mio::Ready {
+    #[cfg(unix)]
+    let ready = mio::unix::UnixReady::priority().into();
+
+    #[cfg(not(unix))]
+    let ready = mio::Ready::empty();
+
+    ready
+}
+
 /// Returns a flag containing the hangup status.
 #[inline]
 fn hup() -> mio::Ready {
@@ -0,0 +1,577 @@
+//! A completion-based driver for reads and writes, built on Linux's io_uring.
+//!
+//! Reads and writes on I/O handles created with `Watcher::new_stream` and
+//! `Watcher::new_datagram`, and on files, are submitted to a single io_uring instance instead of
+//! being run as system calls one by one. Submissions are queued and handed to the kernel at once
+//! by the thread polling the first shard of the reactor, where the ring's file descriptor is
+//! registered. The reactor drains the completion queue whenever it becomes readable.
+//!
+//! Sockets are still polled by the reactor: a read or write is only submitted once the socket is
+//! ready, so that the kernel never has to wait on a socket on behalf of the ring.
+//!
+//! The kernel reads into and writes from buffers owned by the driver, so an operation stays sound
+//! even if the task that submitted it stops polling. Each I/O handle keeps its buffers around and
+//! reuses them for the next operations. An I/O handle has at most one read and one write in
+//! flight. If a task stops polling an operation and the handle is polled again, the result of the
+//! read in flight is returned first. The result of the write in flight is only returned if the
+//! handle is polled with the same buffer again, since it tells how much of that buffer has been
+//! written. Otherwise, the write is waited on and its result is discarded.
+
+use std::cmp;
+use std::mem;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use io_uring::{opcode, types, IoUring, Probe};
+use slab::Slab;
+
+use super::Entry;
+use crate::io;
+use crate::task::{budget, Context, Poll, Waker};
+
+/// The number of entries in the submission queue.
+const ENTRIES: u32 = 256;
+
+/// The largest buffer submitted in a single operation.
+const MAX_LEN: usize = 1 << 20;
+
+/// The user data of cancellation requests, whose completions are ignored.
+const CANCEL: u64 = u64::MAX;
+
+/// Returns the io_uring instance, or `None` if the kernel doesn't support it.
+pub(crate) fn ring() -> Option<&'static Ring> {
+    super::REACTOR.ring.as_ref()
+}
+
+/// An io_uring instance along with the operations in flight.
+pub(crate) struct Ring {
+    inner: Mutex<Inner>,
+
+    /// Set to `true` when entries have been pushed into the submission queue but not submitted.
+    unsubmitted: AtomicBool,
+}
+
+struct Inner {
+    /// The submission and completion queues.
+    ring: IoUring,
+
+    /// Operations that have been submitted and not yet taken by their I/O handles.
+    ops: Slab<Op>,
+
+    /// The identifier of the next operation, used to tell apart operations reusing a slab slot.
+    next_id: u32,
+}
+
+/// A read or write operation.
+struct Op {
+    /// The identifier of the operation, stored in the upper half of its user data.
+    id: u32,
+
+    /// The file descriptor the operation is performed on.
+    fd: RawFd,
+
+    /// Whether the operation is a read or a write.
+    kind: Kind,
+
+    /// The buffer the kernel reads into or writes from.
+    ///
+    /// A read fills the spare capacity of the buffer, and its length is set once it completes.
+    buf: Vec<u8>,
+
+    /// The number of bytes to read or write.
+    len: usize,
+
+    /// The progress of the operation.
+    state: State,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Read,
+    Write,
+}
+
+enum State {
+    /// The operation is in flight, and these tasks are waiting on it.
+    Waiting(Vec<Waker>),
+
+    /// The operation has completed.
+    Done(io::Result<usize>),
+
+    /// The I/O handle has been dropped, and the operation is being cancelled.
+    Abandoned,
+}
+
+impl Ring {
+    /// Creates a new io_uring instance, checking that the kernel supports the needed operations.
+    pub(crate) fn new() -> io::Result<Ring> {
+        let ring = IoUring::new(ENTRIES)?;
+
+        let mut probe = Probe::new();
+        ring.submitter().register_probe(&mut probe)?;
+        let supported = [opcode::Read::CODE, opcode::Write::CODE];
+        if !supported.iter().all(|&code| probe.is_supported(code)) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "io_uring doesn't support reads and writes",
+            ));
+        }
+
+        Ok(Ring {
+            inner: Mutex::new(Inner {
+                ring,
+                ops: Slab::new(),
+                next_id: 0,
+            }),
+            unsubmitted: AtomicBool::new(false),
+        })
+    }
+
+    /// Queues a read of up to `len` bytes into `buf`, and returns the user data identifying it.
+    fn read(&self, fd: RawFd, mut buf: Vec<u8>, len: usize) -> io::Result<u64> {
+        let len = cmp::min(len, MAX_LEN);
+        buf.clear();
+        buf.reserve(len);
+        self.push(fd, Kind::Read, buf, len)
+    }
+
+    /// Queues a write of `data`, copied into `buf`, and returns the user data identifying it.
+    fn write(&self, fd: RawFd, mut buf: Vec<u8>, data: &[u8]) -> io::Result<u64> {
+        let len = cmp::min(data.len(), MAX_LEN);
+        buf.clear();
+        buf.extend_from_slice(&data[..len]);
+        self.push(fd, Kind::Write, buf, len)
+    }
+
+    fn push(&self, fd: RawFd, kind: Kind, buf: Vec<u8>, len: usize) -> io::Result<u64> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let id = inner.next_id;
+        inner.next_id = inner.next_id.wrapping_add(1);
+        let index = inner.ops.insert(Op {
+            id,
+            fd,
+            kind,
+            buf,
+            len,
+            state: State::Waiting(Vec::new()),
+        });
+        let user_data = (u64::from(id) << 32) | index as u64;
+
+        if let Err(err) = inner.push_transfer(user_data) {
+            inner.ops.remove(index);
+            return Err(err);
+        }
+        drop(inner);
+
+        self.schedule();
+        Ok(user_data)
+    }
+
+    /// Makes sure queued entries get submitted.
+    ///
+    /// The first entry queued after a submission wakes up the thread polling the ring's shard,
+    /// which submits all entries queued by then before it waits for events again.
+    fn schedule(&self) {
+        if !self.unsubmitted.swap(true, Ordering::SeqCst) {
+            super::REACTOR.notify();
+        }
+    }
+
+    /// Submits queued entries, and processes operations that have completed right away.
+    ///
+    /// Returns `true` if any tasks have been woken up.
+    pub(crate) fn flush(&self) -> bool {
+        if !self.unsubmitted.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        let mut inner = self.inner.lock().unwrap();
+
+        // Errors like a full completion queue are transient and leave the entries queued, so try
+        // again on the next turn.
+        if inner.ring.submit().is_err() {
+            self.schedule();
+        }
+
+        let wakers = inner.reap();
+        drop(inner);
+        let woken = !wakers.is_empty();
+        wake_all(wakers);
+        woken
+    }
+
+    /// Polls an operation, taking its result and buffer once it completes.
+    fn poll(&self, cx: &mut Context<'_>, user_data: u64) -> Poll<(io::Result<usize>, Vec<u8>)> {
+        let mut inner = self.inner.lock().unwrap();
+        let index = user_data as u32 as usize;
+
+        match &mut inner.ops[index].state {
+            State::Waiting(wakers) => {
+                if wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+            State::Done(_) => {
+                let op = inner.ops.remove(index);
+                match op.state {
+                    State::Done(res) => Poll::Ready((res, op.buf)),
+                    _ => unreachable!(),
+                }
+            }
+            State::Abandoned => unreachable!("polled an abandoned operation"),
+        }
+    }
+
+    /// Abandons an operation whose I/O handle is being dropped.
+    ///
+    /// The operation is cancelled if still in flight, and its buffer is freed once the kernel
+    /// stops using it.
+    fn abandon(&self, user_data: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        let index = user_data as u32 as usize;
+
+        match inner.ops[index].state {
+            State::Done(_) => {
+                inner.ops.remove(index);
+            }
+            _ => {
+                inner.ops[index].state = State::Abandoned;
+                let sqe = opcode::AsyncCancel::new(user_data)
+                    .build()
+                    .user_data(CANCEL);
+                if inner.push(&sqe).is_ok() {
+                    drop(inner);
+                    self.schedule();
+                }
+            }
+        }
+    }
+
+    /// Processes completed operations, waking up tasks waiting on them.
+    pub(crate) fn complete(&self) {
+        let wakers = self.inner.lock().unwrap().reap();
+        wake_all(wakers);
+    }
+}
+
+impl AsRawFd for Ring {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.lock().unwrap().ring.as_raw_fd()
+    }
+}
+
+impl Inner {
+    /// Pushes an entry into the submission queue.
+    fn push(&mut self, sqe: &io_uring::squeue::Entry) -> io::Result<()> {
+        // If the submission queue is full, submit its entries to make room.
+        while unsafe { self.ring.submission().push(sqe) }.is_err() {
+            self.ring.submit()?;
+        }
+        Ok(())
+    }
+
+    /// Pushes the read or write of an operation.
+    fn push_transfer(&mut self, user_data: u64) -> io::Result<()> {
+        let op = &mut self.ops[user_data as u32 as usize];
+
+        // The offset of `-1` uses the file position, as `read(2)` and `write(2)` do.
+        let fd = types::Fd(op.fd);
+        let len = op.len as u32;
+        let sqe = match op.kind {
+            Kind::Read => opcode::Read::new(fd, op.buf.as_mut_ptr(), len)
+                .offset(u64::MAX)
+                .build(),
+            Kind::Write => opcode::Write::new(fd, op.buf.as_ptr(), len)
+                .offset(u64::MAX)
+                .build(),
+        };
+        self.push(&sqe.user_data(user_data))
+    }
+
+    /// Drains the completion queue and returns the wakers of completed operations.
+    fn reap(&mut self) -> Vec<Waker> {
+        let completions: Vec<_> = self
+            .ring
+            .completion()
+            .map(|cqe| (cqe.user_data(), cqe.result()))
+            .collect();
+
+        let mut wakers = Vec::new();
+        for (user_data, res) in completions {
+            if user_data == CANCEL {
+                continue;
+            }
+
+            // Skip completions of operations that have already been taken.
+            let index = user_data as u32 as usize;
+            match self.ops.get(index) {
+                Some(op) if u64::from(op.id) == user_data >> 32 => {}
+                _ => continue,
+            }
+
+            if let State::Abandoned = self.ops[index].state {
+                self.ops.remove(index);
+                continue;
+            }
+
+            let res = if res < 0 {
+                Err(io::Error::from_raw_os_error(-res))
+            } else {
+                Ok(res as usize)
+            };
+            match res {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    if let Err(err) = self.push_transfer(user_data) {
+                        self.finish(index, Err(err), &mut wakers);
+                    }
+                }
+                res => self.finish(index, res, &mut wakers),
+            }
+        }
+        wakers
+    }
+
+    /// Completes an operation with the given result.
+    fn finish(&mut self, index: usize, res: io::Result<usize>, wakers: &mut Vec<Waker>) {
+        let op = &mut self.ops[index];
+        if let Ok(n) = res {
+            if op.kind == Kind::Read {
+                // The kernel has filled the first `n` bytes of the spare capacity.
+                unsafe {
+                    op.buf.set_len(n);
+                }
+            }
+        }
+        if let State::Waiting(list) = mem::replace(&mut op.state, State::Done(res)) {
+            wakers.extend(list);
+        }
+    }
+}
+
+fn wake_all(wakers: Vec<Waker>) {
+    for w in wakers {
+        w.wake();
+    }
+}
+
+fn is_would_block(res: &io::Result<usize>) -> bool {
+    match res {
+        Err(err) => err.kind() == io::ErrorKind::WouldBlock,
+        Ok(_) => false,
+    }
+}
+
+/// The reads and writes of an I/O handle submitted to io_uring.
+pub(crate) struct Ops {
+    /// The io_uring instance the operations are submitted to.
+    ring: &'static Ring,
+
+    /// The file descriptor of the I/O handle.
+    fd: RawFd,
+
+    /// The reactor entry of a socket, used to submit operations only once the socket is ready.
+    entry: Option<Arc<Entry>>,
+
+    /// Set to `true` if the I/O handle reads whole datagrams rather than a byte stream.
+    datagram: bool,
+
+    /// The read in flight and any data it returned that didn't fit into the caller's buffer.
+    read: Mutex<Reading>,
+
+    /// The write in flight.
+    write: Mutex<Writing>,
+}
+
+struct Reading {
+    op: Option<u64>,
+
+    /// The buffer of the last read, reused by the next one.
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+struct Writing {
+    op: Option<u64>,
+
+    /// The buffer of the last write, reused by the next one.
+    buf: Vec<u8>,
+
+    /// The address and length of the caller's buffer the write in flight was submitted for.
+    submitted: (usize, usize),
+}
+
+impl Ops {
+    /// Creates the operation state of a file, or returns `None` if io_uring is not supported.
+    pub(crate) fn new(fd: RawFd) -> Option<Ops> {
+        Ops::with_entry(fd, None, false)
+    }
+
+    /// Creates the operation state of a socket registered in the reactor, or returns `None` if
+    /// io_uring is not supported.
+    pub(super) fn socket(fd: RawFd, entry: Arc<Entry>, datagram: bool) -> Option<Ops> {
+        Ops::with_entry(fd, Some(entry), datagram)
+    }
+
+    fn with_entry(fd: RawFd, entry: Option<Arc<Entry>>, datagram: bool) -> Option<Ops> {
+        Some(Ops {
+            ring: ring()?,
+            fd,
+            entry,
+            datagram,
+            read: Mutex::new(Reading {
+                op: None,
+                buf: Vec::new(),
+                pos: 0,
+            }),
+            write: Mutex::new(Writing {
+                op: None,
+                buf: Vec::new(),
+                submitted: (0, 0),
+            }),
+        })
+    }
+
+    /// Reads some bytes into `buf`.
+    pub(crate) fn poll_read(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        futures_core::ready!(budget::poll_proceed(cx));
+
+        let mut reading = self.read.lock().unwrap();
+        loop {
+            // Return data left over from the last read first.
+            if reading.pos < reading.buf.len() {
+                let n = cmp::min(buf.len(), reading.buf.len() - reading.pos);
+                buf[..n].copy_from_slice(&reading.buf[reading.pos..reading.pos + n]);
+                reading.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            if buf.is_empty() && reading.op.is_none() {
+                return Poll::Ready(Ok(0));
+            }
+
+            let user_data = match reading.op {
+                Some(user_data) => user_data,
+                None => {
+                    // Wait until the socket is readable. Events coming in while the read is in
+                    // flight make it readable again.
+                    if let Some(entry) = &self.entry {
+                        let mut readers = entry.readers.lock().unwrap();
+                        if !readers.ready {
+                            if readers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+                                readers.wakers.push(cx.waker().clone());
+                            }
+                            return Poll::Pending;
+                        }
+                        readers.ready = false;
+                    }
+
+                    let data = mem::take(&mut reading.buf);
+                    reading.pos = 0;
+                    let user_data = self.ring.read(self.fd, data, buf.len())?;
+                    reading.op = Some(user_data);
+                    user_data
+                }
+            };
+
+            let (res, data) = futures_core::ready!(self.ring.poll(cx, user_data));
+            reading.op = None;
+            reading.buf = data;
+
+            // If the socket turned out not to be readable, wait for the reactor to report it and
+            // then try again. Otherwise, more data may be available right away.
+            if let Some(entry) = &self.entry {
+                if is_would_block(&res) {
+                    continue;
+                }
+                entry.readers.lock().unwrap().ready = true;
+            }
+
+            let n = match res {
+                Ok(_) => cmp::min(buf.len(), reading.buf.len()),
+                Err(err) => {
+                    reading.buf.clear();
+                    return Poll::Ready(Err(err));
+                }
+            };
+            buf[..n].copy_from_slice(&reading.buf[..n]);
+
+            // Keep the rest of the data unless it is the truncated part of a datagram.
+            reading.pos = if self.datagram { reading.buf.len() } else { n };
+            return Poll::Ready(Ok(n));
+        }
+    }
+
+    /// Writes some bytes from `buf`.
+    pub(crate) fn poll_write(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        futures_core::ready!(budget::poll_proceed(cx));
+
+        let mut writing = self.write.lock().unwrap();
+        let submitted = (buf.as_ptr() as usize, cmp::min(buf.len(), MAX_LEN));
+
+        loop {
+            let user_data = match writing.op {
+                Some(user_data) => user_data,
+                None => {
+                    if buf.is_empty() {
+                        return Poll::Ready(Ok(0));
+                    }
+
+                    // Wait until the socket is writable. Events coming in while the write is in
+                    // flight make it writable again.
+                    if let Some(entry) = &self.entry {
+                        let mut writers = entry.writers.lock().unwrap();
+                        if !writers.ready {
+                            if writers.wakers.iter().all(|w| !w.will_wake(cx.waker())) {
+                                writers.wakers.push(cx.waker().clone());
+                            }
+                            return Poll::Pending;
+                        }
+                        writers.ready = false;
+                    }
+
+                    let data = mem::take(&mut writing.buf);
+                    let user_data = self.ring.write(self.fd, data, buf)?;
+                    writing.op = Some(user_data);
+                    writing.submitted = submitted;
+                    user_data
+                }
+            };
+
+            let (res, data) = futures_core::ready!(self.ring.poll(cx, user_data));
+            writing.op = None;
+            writing.buf = data;
+
+            // If the socket turned out not to be writable, wait for the reactor to report it and
+            // then try again. Otherwise, more data may be written right away.
+            if let Some(entry) = &self.entry {
+                if is_would_block(&res) {
+                    continue;
+                }
+                entry.writers.lock().unwrap().ready = true;
+            }
+
+            // If the write was submitted for the same buffer, its result tells how much of the
+            // buffer has been written. Otherwise, the result belongs to a buffer that has been
+            // abandoned.
+            if writing.submitted == submitted {
+                return Poll::Ready(res);
+            }
+        }
+    }
+}
+
+impl Drop for Ops {
+    fn drop(&mut self) {
+        if let Some(user_data) = self.read.get_mut().unwrap().op.take() {
+            self.ring.abandon(user_data);
+        }
+        if let Some(user_data) = self.write.get_mut().unwrap().op.take() {
+            self.ring.abandon(user_data);
+        }
+    }
+}
@@ -8,6 +8,7 @@
 //! # Organization
 //!
 //! * [`TcpListener`] and [`TcpStream`] provide functionality for communication over TCP
+//! * [`TcpSocket`] sets socket options before turning into a [`TcpListener`] or a [`TcpStream`]
 //! * [`UdpSocket`] provides functionality for communication over UDP
 //! * [`IpAddr`] represents IP addresses of either IPv4 or IPv6; [`Ipv4Addr`] and
 //!   [`Ipv6Addr`] are respectively IPv4 and IPv6 addresses
@@ -24,6 +25,7 @@
 //! [`SocketAddrV4`]: struct.SocketAddrV4.html
 //! [`SocketAddrV6`]: struct.SocketAddrV6.html
 //! [`TcpListener`]: struct.TcpListener.html
+//! [`TcpSocket`]: struct.TcpSocket.html
 //! [`TcpStream`]: struct.TcpStream.html
 //! [`ToSocketAddrs`]: trait.ToSocketAddrs.html
 //! [`UdpSocket`]: struct.UdpSocket.html
@@ -65,6 +67,11 @@ pub use addr::ToSocketAddrs;
 pub use tcp::{Incoming, TcpListener, TcpStream};
 pub use udp::UdpSocket;
 
+cfg_unstable! {
+    pub use tcp::TcpSocket;
+    pub use tcp::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
+}
+
 mod addr;
 pub(crate) mod driver;
 mod tcp;
@@ -49,7 +49,7 @@ use crate::task::{Context, Poll};
 /// ```
 #[derive(Debug)]
 pub struct TcpListener {
-    watcher: Watcher<mio::net::TcpListener>,
+    pub(super) watcher: Watcher<mio::net::TcpListener>,
 }
 
 impl TcpListener {
@@ -120,7 +120,7 @@ impl TcpListener {
 
         let mio_stream = mio::net::TcpStream::from_stream(io)?;
         let stream = TcpStream {
-            watcher: Arc::new(Watcher::new(mio_stream)),
+            watcher: Arc::new(Watcher::new_stream(mio_stream)),
         };
         Ok((stream, addr))
     }
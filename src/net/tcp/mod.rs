@@ -3,3 +3,11 @@ pub use stream::TcpStream;
 
 mod listener;
 mod stream;
+
+cfg_unstable! {
+    pub use socket::TcpSocket;
+    pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
+
+    mod socket;
+    mod split;
+}
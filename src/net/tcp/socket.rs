@@ -0,0 +1,237 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+
+use crate::future;
+use crate::io;
+use crate::net::driver::Watcher;
+use crate::net::{TcpListener, TcpStream};
+
+/// A TCP socket that has not been turned into a listener or a stream yet.
+///
+/// [`TcpListener::bind`] and [`TcpStream::connect`] create sockets with the default options. A
+/// `TcpSocket` makes it possible to set options that only take effect before binding or
+/// connecting, such as address reuse and buffer sizes, and to bind a stream to a specific local
+/// address before connecting it.
+///
+/// The socket is turned into a [`TcpListener`] with [`listen`], or into a [`TcpStream`] with
+/// [`connect`].
+///
+/// [`TcpListener::bind`]: struct.TcpListener.html#method.bind
+/// [`TcpStream::connect`]: struct.TcpStream.html#method.connect
+/// [`TcpListener`]: struct.TcpListener.html
+/// [`TcpStream`]: struct.TcpStream.html
+/// [`listen`]: #method.listen
+/// [`connect`]: #method.connect
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+/// #
+/// use async_std::net::TcpSocket;
+///
+/// let socket = TcpSocket::new_v4()?;
+/// socket.set_reuseaddr(true)?;
+/// socket.set_recv_buffer_size(1 << 20)?;
+/// socket.bind("127.0.0.1:8080".parse().unwrap())?;
+///
+/// let listener = socket.listen(1024)?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub struct TcpSocket {
+    inner: Socket,
+}
+
+impl TcpSocket {
+    /// Creates a new IPv4 TCP socket.
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        TcpSocket::new(Domain::IPV4)
+    }
+
+    /// Creates a new IPv6 TCP socket.
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        TcpSocket::new(Domain::IPV6)
+    }
+
+    /// Creates a new TCP socket in the address family of `addr`.
+    pub fn new_for_addr(addr: SocketAddr) -> io::Result<TcpSocket> {
+        match addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4(),
+            SocketAddr::V6(_) => TcpSocket::new_v6(),
+        }
+    }
+
+    fn new(domain: Domain) -> io::Result<TcpSocket> {
+        let inner = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
+        Ok(TcpSocket { inner })
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// Allows binding to an address that is still in the `TIME_WAIT` state, typically after
+    /// restarting a server.
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.inner.set_reuse_address(reuseaddr)
+    }
+
+    /// Gets the value of the `SO_REUSEADDR` option on this socket.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.inner.reuse_address()
+    }
+
+    /// Sets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// Allows several sockets to bind to the same address, with the kernel spreading incoming
+    /// connections between them.
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.inner.set_reuse_port(reuseport)
+    }
+
+    /// Gets the value of the `SO_REUSEPORT` option on this socket.
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    pub fn reuseport(&self) -> io::Result<bool> {
+        self.inner.reuse_port()
+    }
+
+    /// Sets the size of the receive buffer (the `SO_RCVBUF` option).
+    ///
+    /// The operating system may round the size or double it to account for bookkeeping.
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.inner.set_recv_buffer_size(size)
+    }
+
+    /// Gets the size of the receive buffer (the `SO_RCVBUF` option).
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.inner.recv_buffer_size()
+    }
+
+    /// Sets the size of the send buffer (the `SO_SNDBUF` option).
+    ///
+    /// The operating system may round the size or double it to account for bookkeeping.
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.inner.set_send_buffer_size(size)
+    }
+
+    /// Gets the size of the send buffer (the `SO_SNDBUF` option).
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.inner.send_buffer_size()
+    }
+
+    /// Sets the value of the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// If set, an IPv6 socket only accepts IPv6 connections. Otherwise, it also accepts IPv4
+    /// connections through IPv4-mapped addresses. This option fails on IPv4 sockets.
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        self.inner.set_only_v6(only_v6)
+    }
+
+    /// Gets the value of the `IPV6_V6ONLY` option on this socket.
+    pub fn only_v6(&self) -> io::Result<bool> {
+        self.inner.only_v6()
+    }
+
+    /// Binds the socket to the given local address.
+    ///
+    /// Binding before [`connect`] selects the local address and port of the stream.
+    ///
+    /// [`connect`]: #method.connect
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.inner.bind(&SockAddr::from(addr))
+    }
+
+    /// Returns the local address the socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        let addr = self.inner.local_addr()?;
+        addr.as_socket().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "socket is not an inet socket")
+        })
+    }
+
+    /// Starts listening on the bound address, turning the socket into a [`TcpListener`].
+    ///
+    /// The `backlog` is the maximum number of pending connections the operating system queues
+    /// before refusing new ones.
+    ///
+    /// [`TcpListener`]: struct.TcpListener.html
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        let backlog = backlog.min(i32::MAX as u32) as i32;
+        self.inner.listen(backlog)?;
+
+        let mio_listener = mio::net::TcpListener::from_std(self.inner.into())?;
+        Ok(TcpListener {
+            watcher: Watcher::new(mio_listener),
+        })
+    }
+
+    /// Connects the socket to the given remote address, turning it into a [`TcpStream`].
+    ///
+    /// [`TcpStream`]: struct.TcpStream.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v4()?;
+    /// socket.bind("127.0.0.1:0".parse().unwrap())?;
+    ///
+    /// let stream = socket.connect("127.0.0.1:8080".parse().unwrap()).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        // The connection is in progress when mio returns, so wait for write readiness and then
+        // check whether it has been established.
+        let mio_stream = mio::net::TcpStream::connect_stream(self.inner.into(), &addr)?;
+        let watcher = Watcher::new_stream(mio_stream);
+
+        future::poll_fn(|cx| watcher.poll_write_ready(cx)).await;
+
+        match watcher.get_ref().take_error()? {
+            None => Ok(TcpStream {
+                watcher: Arc::new(watcher),
+            }),
+            Some(err) => Err(err),
+        }
+    }
+}
+
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpSocket")
+            .field("local_addr", &self.local_addr().ok())
+            .finish()
+    }
+}
+
+cfg_unix! {
+    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+    impl AsRawFd for TcpSocket {
+        fn as_raw_fd(&self) -> RawFd {
+            self.inner.as_raw_fd()
+        }
+    }
+
+    impl FromRawFd for TcpSocket {
+        unsafe fn from_raw_fd(fd: RawFd) -> TcpSocket {
+            TcpSocket {
+                inner: Socket::from_raw_fd(fd),
+            }
+        }
+    }
+
+    impl IntoRawFd for TcpSocket {
+        fn into_raw_fd(self) -> RawFd {
+            self.inner.into_raw_fd()
+        }
+    }
+}
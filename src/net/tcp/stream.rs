@@ -2,10 +2,17 @@ use std::io::{IoSlice, IoSliceMut, Read as _, Write as _};
 use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
+#[cfg(feature = "unstable")]
+use std::time::Duration;
+
+#[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+use socket2::{SockRef, TcpKeepalive};
 
 use crate::future;
 use crate::io::{self, Read, Write};
 use crate::net::driver::Watcher;
+#[cfg(feature = "unstable")]
+use crate::net::tcp::split::{self, OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf};
 use crate::net::ToSocketAddrs;
 use crate::task::{Context, Poll};
 
@@ -80,7 +87,7 @@ impl TcpStream {
             // be sure the connection has either been established or there was an
             // error which we check for afterwards.
             let watcher = match mio::net::TcpStream::connect(&addr) {
-                Ok(s) => Watcher::new(s),
+                Ok(s) => Watcher::new_stream(s),
                 Err(e) => {
                     last_err = Some(e);
                     continue;
@@ -267,6 +274,280 @@ impl TcpStream {
         self.watcher.get_ref().set_nodelay(nodelay)
     }
 
+    /// Gets the keepalive idle time of this socket, or `None` if keepalive is disabled.
+    ///
+    /// For more information about this option, see [`set_keepalive`].
+    ///
+    /// [`set_keepalive`]: #method.set_keepalive
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn keepalive(&self) -> io::Result<Option<Duration>> {
+        self.watcher.get_ref().keepalive()
+    }
+
+    /// Enables or disables keepalive probes on this socket (the `SO_KEEPALIVE` option).
+    ///
+    /// If `Some`, the first probe is sent after the connection has been idle for the given
+    /// duration. Without keepalive, a connection whose peer disappeared, for example behind a
+    /// NAT that dropped its mapping, is never closed when no data is sent over it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    ///
+    /// stream.set_keepalive(Some(Duration::from_secs(60)))?;
+    /// assert_eq!(stream.keepalive()?, Some(Duration::from_secs(60)));
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_keepalive(&self, keepalive: Option<Duration>) -> io::Result<()> {
+        self.watcher.get_ref().set_keepalive(keepalive)
+    }
+
+    /// Gets the interval between keepalive probes (the `TCP_KEEPINTVL` option).
+    ///
+    /// For more information about this option, see [`set_keepalive_interval`].
+    ///
+    /// [`set_keepalive_interval`]: #method.set_keepalive_interval
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        self.sock_ref().keepalive_interval()
+    }
+
+    /// Sets the interval between keepalive probes (the `TCP_KEEPINTVL` option).
+    ///
+    /// This also enables keepalive if it is disabled, keeping the idle time configured with
+    /// [`set_keepalive`].
+    ///
+    /// [`set_keepalive`]: #method.set_keepalive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    ///
+    /// stream.set_keepalive(Some(Duration::from_secs(60)))?;
+    /// stream.set_keepalive_interval(Duration::from_secs(10))?;
+    /// stream.set_keepalive_retries(3)?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        let keepalive = TcpKeepalive::new().with_interval(interval);
+        self.sock_ref().set_tcp_keepalive(&keepalive)
+    }
+
+    /// Gets the number of unanswered keepalive probes before the connection is dropped (the
+    /// `TCP_KEEPCNT` option).
+    ///
+    /// For more information about this option, see [`set_keepalive_retries`].
+    ///
+    /// [`set_keepalive_retries`]: #method.set_keepalive_retries
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn keepalive_retries(&self) -> io::Result<u32> {
+        self.sock_ref().keepalive_retries()
+    }
+
+    /// Sets the number of unanswered keepalive probes before the connection is dropped (the
+    /// `TCP_KEEPCNT` option).
+    ///
+    /// This also enables keepalive if it is disabled, keeping the idle time configured with
+    /// [`set_keepalive`].
+    ///
+    /// [`set_keepalive`]: #method.set_keepalive
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_keepalive_retries(&self, retries: u32) -> io::Result<()> {
+        let keepalive = TcpKeepalive::new().with_retries(retries);
+        self.sock_ref().set_tcp_keepalive(&keepalive)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`set_linger`].
+    ///
+    /// [`set_linger`]: #method.set_linger
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.watcher.get_ref().linger()
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// If `Some`, closing the socket blocks for up to the given duration while unsent data is
+    /// delivered. A zero duration discards unsent data and resets the connection on close.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    ///
+    /// stream.set_linger(Some(Duration::from_secs(0)))?;
+    /// assert_eq!(stream.linger()?, Some(Duration::from_secs(0)));
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.watcher.get_ref().set_linger(linger)
+    }
+
+    /// Gets the size of the receive buffer (the `SO_RCVBUF` option).
+    ///
+    /// For more information about this option, see [`set_recv_buffer_size`].
+    ///
+    /// [`set_recv_buffer_size`]: #method.set_recv_buffer_size
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.watcher.get_ref().recv_buffer_size()
+    }
+
+    /// Sets the size of the receive buffer (the `SO_RCVBUF` option).
+    ///
+    /// The operating system may round the size or double it to account for bookkeeping.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    ///
+    /// stream.set_recv_buffer_size(1 << 20)?;
+    /// stream.set_send_buffer_size(1 << 20)?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.watcher.get_ref().set_recv_buffer_size(size)
+    }
+
+    /// Gets the size of the send buffer (the `SO_SNDBUF` option).
+    ///
+    /// For more information about this option, see [`set_send_buffer_size`].
+    ///
+    /// [`set_send_buffer_size`]: #method.set_send_buffer_size
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.watcher.get_ref().send_buffer_size()
+    }
+
+    /// Sets the size of the send buffer (the `SO_SNDBUF` option).
+    ///
+    /// The operating system may round the size or double it to account for bookkeeping.
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.watcher.get_ref().set_send_buffer_size(size)
+    }
+
+    /// Gets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// For more information about this option, see [`set_user_timeout`].
+    ///
+    /// [`set_user_timeout`]: #method.set_user_timeout
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        self.sock_ref().tcp_user_timeout()
+    }
+
+    /// Sets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// If `Some`, the connection is dropped when sent data stays unacknowledged for longer than
+    /// the given duration. `None` restores the system default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::net::TcpStream;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    ///
+    /// stream.set_user_timeout(Some(Duration::from_secs(30)))?;
+    /// assert_eq!(stream.user_timeout()?, Some(Duration::from_secs(30)));
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.sock_ref().set_tcp_user_timeout(timeout)
+    }
+
+    /// Gets the value of the `TCP_QUICKACK` option on this socket.
+    ///
+    /// For more information about this option, see [`set_quickack`].
+    ///
+    /// [`set_quickack`]: #method.set_quickack
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn quickack(&self) -> io::Result<bool> {
+        self.sock_ref().quickack()
+    }
+
+    /// Sets the value of the `TCP_QUICKACK` option on this socket.
+    ///
+    /// If set, acknowledgements are sent immediately rather than delayed. The kernel may clear
+    /// the option again on its own, so it is typically set after every read.
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
+        self.sock_ref().set_quickack(quickack)
+    }
+
+    /// Gets the value of the `SO_ERROR` option on this socket, clearing it.
+    ///
+    /// Returns the last error that occurred on the socket, or `None` if there is none.
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.watcher.get_ref().take_error()
+    }
+
+    /// Borrows the underlying socket for setting options mio doesn't expose.
+    #[cfg(all(feature = "unstable", any(target_os = "linux", target_os = "android")))]
+    fn sock_ref(&self) -> SockRef<'_> {
+        SockRef::from(self.watcher.get_ref())
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     ///
     /// This method will cause all pending and future I/O on the specified portions to return
@@ -291,6 +572,74 @@ impl TcpStream {
     pub fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
         self.watcher.get_ref().shutdown(how)
     }
+
+    /// Splits the stream into a reading half and a writing half borrowed from it.
+    ///
+    /// The halves can be used concurrently, for example by joining a future that reads with a
+    /// future that writes. They don't share any lock.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::io;
+    /// use async_std::net::TcpStream;
+    ///
+    /// let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
+    /// let (mut reader, mut writer) = stream.split();
+    ///
+    /// // Echo everything back to the peer.
+    /// io::copy(&mut reader, &mut writer).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn split(&mut self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        split::split(self)
+    }
+
+    /// Splits the stream into a reading half and a writing half that can be moved into
+    /// different tasks.
+    ///
+    /// The halves don't share any lock, and can be put back together with [`reunite`]. Dropping
+    /// the writing half shuts down the write side of the connection, unless disabled with
+    /// [`set_shutdown_on_drop`].
+    ///
+    /// [`reunite`]: struct.OwnedReadHalf.html#method.reunite
+    /// [`set_shutdown_on_drop`]: struct.OwnedWriteHalf.html#method.set_shutdown_on_drop
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::net::TcpStream;
+    /// use async_std::prelude::*;
+    /// use async_std::task;
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080").await?;
+    /// let (mut reader, mut writer) = stream.into_split();
+    ///
+    /// let handle = task::spawn(async move {
+    ///     writer.write_all(b"hello").await?;
+    ///     Ok::<_, std::io::Error>(writer)
+    /// });
+    ///
+    /// let mut buf = vec![0u8; 1024];
+    /// let n = reader.read(&mut buf).await?;
+    ///
+    /// let writer = handle.await?;
+    /// let stream = reader.reunite(writer).unwrap();
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        split::into_split(self)
+    }
 }
 
 impl Read for TcpStream {
@@ -317,7 +666,8 @@ impl Read for &TcpStream {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        self.watcher.poll_read_with(cx, |mut inner| inner.read(buf))
+        self.watcher
+            .poll_read_buf(cx, buf, |mut inner, buf| inner.read(buf))
     }
 }
 
@@ -354,7 +704,7 @@ impl Write for &TcpStream {
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
         self.watcher
-            .poll_write_with(cx, |mut inner| inner.write(buf))
+            .poll_write_buf(cx, buf, |mut inner, buf| inner.write(buf))
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
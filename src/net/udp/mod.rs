@@ -77,7 +77,7 @@ impl UdpSocket {
             match mio::net::UdpSocket::bind(&addr) {
                 Ok(mio_socket) => {
                     return Ok(UdpSocket {
-                        watcher: Watcher::new(mio_socket),
+                        watcher: Watcher::new_datagram(mio_socket),
                     });
                 }
                 Err(err) => last_err = Some(err),
@@ -267,7 +267,7 @@ impl UdpSocket {
     /// # Ok(()) }) }
     /// ```
     pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        future::poll_fn(|cx| self.watcher.poll_write_with(cx, |inner| inner.send(buf)))
+        future::poll_fn(|cx| self.watcher.poll_write_buf(cx, buf, |s, buf| s.send(buf)))
             .await
             .context(|| {
                 use std::fmt::Write;
@@ -303,7 +303,7 @@ impl UdpSocket {
     /// # Ok(()) }) }
     /// ```
     pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-        future::poll_fn(|cx| self.watcher.poll_read_with(cx, |inner| inner.recv(buf)))
+        future::poll_fn(|cx| self.watcher.poll_read_buf(cx, buf, |s, buf| s.recv(buf)))
             .await
             .context(|| {
                 use std::fmt::Write;
@@ -500,7 +500,7 @@ impl From<std::net::UdpSocket> for UdpSocket {
     fn from(socket: std::net::UdpSocket) -> UdpSocket {
         let mio_socket = mio::net::UdpSocket::from_socket(socket).unwrap();
         UdpSocket {
-            watcher: Watcher::new(mio_socket),
+            watcher: Watcher::new_datagram(mio_socket),
         }
     }
 }
@@ -0,0 +1,447 @@
+use std::fmt;
+use std::io::{Read as _, Write as _};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+
+use mio::unix::EventedFd;
+use mio::{Evented, PollOpt, Ready, Token};
+
+use crate::future;
+use crate::io::{self, Read, Write};
+use crate::net::driver::Watcher;
+use crate::os::unix::io::{Interest, Mode};
+use crate::task::{Context, Poll};
+
+/// An I/O handle registered in the reactor.
+///
+/// This type wraps any I/O handle backed by a file descriptor, like netlink sockets, eventfd,
+/// inotify, serial ports, or pipes, and lets tasks wait until it becomes readable or writable.
+/// The file descriptor is registered in the reactor when the handle is created and deregistered
+/// when it is dropped.
+///
+/// The file descriptor must be in non-blocking mode, otherwise operations on it block the
+/// executor thread.
+///
+/// Operations are best performed with [`read_with`] and [`write_with`], which retry the
+/// operation whenever it fails with [`io::ErrorKind::WouldBlock`] and clear the readiness. When
+/// performing operations directly on [`get_ref`] after waiting with [`readable`] or
+/// [`writable`], the readiness stays set until it is cleared with [`ReadyGuard::clear_ready`].
+///
+/// By default the handle is registered for all readiness events in edge-triggered mode. Use
+/// [`with_interest`] to register only for some events, or in a different [`Mode`].
+///
+/// If the inner handle implements [`std::io::Read`] or [`std::io::Write`], this type implements
+/// [`AsyncRead`] or [`AsyncWrite`] too.
+///
+/// [`read_with`]: #method.read_with
+/// [`write_with`]: #method.write_with
+/// [`readable`]: #method.readable
+/// [`writable`]: #method.writable
+/// [`get_ref`]: #method.get_ref
+/// [`ReadyGuard::clear_ready`]: struct.ReadyGuard.html#method.clear_ready
+/// [`with_interest`]: #method.with_interest
+/// [`Mode`]: enum.Mode.html
+/// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+/// [`std::io::Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [`std::io::Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+/// [`AsyncRead`]: https://docs.rs/futures/0.3/futures/io/trait.AsyncRead.html
+/// [`AsyncWrite`]: https://docs.rs/futures/0.3/futures/io/trait.AsyncWrite.html
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+/// #
+/// use std::io::Read;
+/// use std::os::unix::net::UnixStream;
+///
+/// use async_std::os::unix::io::Async;
+///
+/// let (a, b) = UnixStream::pair()?;
+/// a.set_nonblocking(true)?;
+/// let a = Async::new(a)?;
+///
+/// // Wait until the socket is readable, and read from it.
+/// let mut buf = [0u8; 1024];
+/// let n = a.read_with(|mut a| a.read(&mut buf)).await?;
+/// #
+/// # Ok(()) }) }
+/// ```
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub struct Async<T: AsRawFd> {
+    watcher: Watcher<Source<T>>,
+}
+
+impl<T: AsRawFd> Async<T> {
+    /// Registers an I/O handle in the reactor.
+    ///
+    /// The file descriptor must be in non-blocking mode.
+    pub fn new(io: T) -> io::Result<Async<T>> {
+        Ok(Async {
+            watcher: Watcher::try_new(Source(io))?,
+        })
+    }
+
+    /// Registers an I/O handle in the reactor for the given readiness events in the given mode.
+    ///
+    /// Waiting for readiness that is not part of `interest` never completes.
+    ///
+    /// The file descriptor must be in non-blocking mode.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::net::TcpStream;
+    ///
+    /// use async_std::os::unix::io::{Async, Interest, Mode};
+    ///
+    /// let stream = TcpStream::connect("127.0.0.1:8080")?;
+    /// stream.set_nonblocking(true)?;
+    /// let interest = Interest::READABLE | Interest::PRIORITY;
+    /// let stream = Async::with_interest(stream, interest, Mode::Level)?;
+    ///
+    /// // Wait until out-of-band data arrives.
+    /// stream.priority().await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub fn with_interest(io: T, interest: Interest, mode: Mode) -> io::Result<Async<T>> {
+        Ok(Async {
+            watcher: Watcher::try_with_interest(Source(io), interest.to_ready(), mode.to_opts())?,
+        })
+    }
+
+    /// Re-arms the I/O handle so that the reactor reports its readiness again.
+    ///
+    /// This is only needed in [`Mode::Oneshot`], where the handle is disarmed after each
+    /// readiness event.
+    ///
+    /// [`Mode::Oneshot`]: enum.Mode.html#variant.Oneshot
+    pub fn rearm(&self) -> io::Result<()> {
+        self.watcher.rearm()
+    }
+
+    /// Returns a reference to the inner I/O handle.
+    pub fn get_ref(&self) -> &T {
+        &self.watcher.get_ref().0
+    }
+
+    /// Returns a mutable reference to the inner I/O handle.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.watcher.get_mut().0
+    }
+
+    /// Deregisters the I/O handle from the reactor and returns it.
+    ///
+    /// The I/O handle is closed if it can't be deregistered.
+    pub fn into_inner(self) -> io::Result<T> {
+        Ok(self.watcher.try_into_inner()?.0)
+    }
+
+    /// Waits until the I/O handle is readable.
+    ///
+    /// The readiness stays set until it is cleared through the returned guard, so clear it once
+    /// an operation fails with [`io::ErrorKind::WouldBlock`] before waiting again.
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::io::ErrorKind;
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// use async_std::os::unix::io::Async;
+    ///
+    /// let socket = UnixDatagram::bind("/tmp/socket")?;
+    /// socket.set_nonblocking(true)?;
+    /// let socket = Async::new(socket)?;
+    ///
+    /// let mut buf = [0u8; 1024];
+    /// let n = loop {
+    ///     let guard = socket.readable().await?;
+    ///     match guard.get_ref().recv(&mut buf) {
+    ///         Err(err) if err.kind() == ErrorKind::WouldBlock => guard.clear_ready(),
+    ///         res => break res?,
+    ///     }
+    /// };
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn readable(&self) -> io::Result<ReadyGuard<'_, T>> {
+        future::poll_fn(|cx| self.watcher.poll_read_ready(cx)).await;
+        Ok(ReadyGuard {
+            io: self,
+            direction: Direction::Read,
+        })
+    }
+
+    /// Waits until the I/O handle is writable.
+    ///
+    /// The readiness stays set until it is cleared through the returned guard, so clear it once
+    /// an operation fails with [`io::ErrorKind::WouldBlock`] before waiting again.
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    pub async fn writable(&self) -> io::Result<ReadyGuard<'_, T>> {
+        future::poll_fn(|cx| self.watcher.poll_write_ready(cx)).await;
+        Ok(ReadyGuard {
+            io: self,
+            direction: Direction::Write,
+        })
+    }
+
+    /// Waits until priority data, like out-of-band data on TCP sockets, can be read.
+    ///
+    /// The readiness is cleared once this method returns, so calling it again waits for the
+    /// next priority data event.
+    pub async fn priority(&self) -> io::Result<()> {
+        future::poll_fn(|cx| self.watcher.poll_priority_ready(cx)).await;
+        self.watcher.clear_priority_ready();
+        Ok(())
+    }
+
+    /// Performs a read operation, waiting until the I/O handle is readable whenever the
+    /// operation fails with [`io::ErrorKind::WouldBlock`].
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// use async_std::os::unix::io::Async;
+    ///
+    /// let socket = UnixDatagram::bind("/tmp/socket")?;
+    /// socket.set_nonblocking(true)?;
+    /// let socket = Async::new(socket)?;
+    ///
+    /// let mut buf = [0u8; 1024];
+    /// let n = socket.read_with(|s| s.recv(&mut buf)).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn read_with<R>(&self, mut op: impl FnMut(&T) -> io::Result<R>) -> io::Result<R> {
+        future::poll_fn(|cx| self.watcher.poll_read_with(cx, |source| op(&source.0))).await
+    }
+
+    /// Performs a read operation that needs mutable access, waiting until the I/O handle is
+    /// readable whenever the operation fails with [`io::ErrorKind::WouldBlock`].
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    pub async fn read_with_mut<R>(
+        &mut self,
+        mut op: impl FnMut(&mut T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        let watcher = &mut self.watcher;
+        future::poll_fn(|cx| watcher.poll_read_with_mut(cx, |source| op(&mut source.0))).await
+    }
+
+    /// Performs a write operation, waiting until the I/O handle is writable whenever the
+    /// operation fails with [`io::ErrorKind::WouldBlock`].
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// use async_std::os::unix::io::Async;
+    ///
+    /// let socket = UnixDatagram::unbound()?;
+    /// socket.set_nonblocking(true)?;
+    /// let socket = Async::new(socket)?;
+    ///
+    /// let msg = b"hello";
+    /// socket.write_with(|s| s.send_to(msg, "/tmp/socket")).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub async fn write_with<R>(&self, mut op: impl FnMut(&T) -> io::Result<R>) -> io::Result<R> {
+        future::poll_fn(|cx| self.watcher.poll_write_with(cx, |source| op(&source.0))).await
+    }
+
+    /// Performs a write operation that needs mutable access, waiting until the I/O handle is
+    /// writable whenever the operation fails with [`io::ErrorKind::WouldBlock`].
+    ///
+    /// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+    pub async fn write_with_mut<R>(
+        &mut self,
+        mut op: impl FnMut(&mut T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        let watcher = &mut self.watcher;
+        future::poll_fn(|cx| watcher.poll_write_with_mut(cx, |source| op(&mut source.0))).await
+    }
+}
+
+impl<T: AsRawFd> AsRawFd for Async<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.get_ref().as_raw_fd()
+    }
+}
+
+impl<T: AsRawFd + fmt::Debug> fmt::Debug for Async<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Async").field("io", self.get_ref()).finish()
+    }
+}
+
+// The inner I/O handle is never pinned.
+impl<T: AsRawFd> Unpin for Async<T> {}
+
+impl<T: AsRawFd + std::io::Read> Read for Async<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut()
+            .watcher
+            .poll_read_with_mut(cx, |source| source.0.read(buf))
+    }
+}
+
+impl<T: AsRawFd> Read for &Async<T>
+where
+    for<'a> &'a T: std::io::Read,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.watcher
+            .poll_read_with(cx, |source| (&source.0).read(buf))
+    }
+}
+
+impl<T: AsRawFd + std::io::Write> Write for Async<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut()
+            .watcher
+            .poll_write_with_mut(cx, |source| source.0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut()
+            .watcher
+            .poll_write_with_mut(cx, |source| source.0.flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T: AsRawFd> Write for &Async<T>
+where
+    for<'a> &'a T: std::io::Write,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.watcher
+            .poll_write_with(cx, |source| (&source.0).write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.watcher
+            .poll_write_with(cx, |source| (&source.0).flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+/// The readiness of an [`Async`] I/O handle, returned by [`readable`] and [`writable`].
+///
+/// Dropping the guard leaves the readiness set, so waiting again returns immediately. Call
+/// [`clear_ready`] once an operation fails with [`io::ErrorKind::WouldBlock`] to wait for the
+/// next readiness event instead.
+///
+/// [`Async`]: struct.Async.html
+/// [`readable`]: struct.Async.html#method.readable
+/// [`writable`]: struct.Async.html#method.writable
+/// [`clear_ready`]: #method.clear_ready
+/// [`io::ErrorKind::WouldBlock`]: ../../../io/enum.ErrorKind.html#variant.WouldBlock
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub struct ReadyGuard<'a, T: AsRawFd> {
+    io: &'a Async<T>,
+    direction: Direction,
+}
+
+/// The kind of readiness a `ReadyGuard` refers to.
+#[derive(Clone, Copy, Debug)]
+enum Direction {
+    Read,
+    Write,
+}
+
+impl<T: AsRawFd> ReadyGuard<'_, T> {
+    /// Returns a reference to the inner I/O handle.
+    pub fn get_ref(&self) -> &T {
+        self.io.get_ref()
+    }
+
+    /// Clears the readiness, so that waiting on it again waits for the next readiness event.
+    pub fn clear_ready(self) {
+        match self.direction {
+            Direction::Read => self.io.watcher.clear_read_ready(),
+            Direction::Write => self.io.watcher.clear_write_ready(),
+        }
+    }
+}
+
+impl<T: AsRawFd + fmt::Debug> fmt::Debug for ReadyGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadyGuard")
+            .field("io", self.get_ref())
+            .field("direction", &self.direction)
+            .finish()
+    }
+}
+
+/// An I/O handle registered in the reactor through its file descriptor.
+struct Source<T>(T);
+
+impl<T: AsRawFd> Evented for Source<T> {
+    fn register(
+        &self,
+        poll: &mio::Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.0.as_raw_fd()).register(poll, token, interest, opts)
+    }
+
+    fn reregister(
+        &self,
+        poll: &mio::Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.0.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &mio::Poll) -> io::Result<()> {
+        EventedFd(&self.0.as_raw_fd()).deregister(poll)
+    }
+}
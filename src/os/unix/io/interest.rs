@@ -0,0 +1,165 @@
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+
+use mio::unix::UnixReady;
+use mio::{PollOpt, Ready};
+
+/// The readiness events an I/O handle is registered for.
+///
+/// Registering only for the events a task actually waits on keeps the reactor from being woken
+/// up by the others. Interests are combined with the `|` operator.
+///
+/// Waiting for readiness that is not part of the registered interest never completes.
+///
+/// # Examples
+///
+/// ```
+/// use async_std::os::unix::io::Interest;
+///
+/// let interest = Interest::READABLE | Interest::PRIORITY;
+/// assert!(interest.is_readable());
+/// assert!(!interest.is_writable());
+/// ```
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Interest(u8);
+
+impl Interest {
+    /// Interest in the I/O handle becoming readable.
+    pub const READABLE: Interest = Interest(0b0001);
+
+    /// Interest in the I/O handle becoming writable.
+    pub const WRITABLE: Interest = Interest(0b0010);
+
+    /// Interest in priority data, like out-of-band data on TCP sockets.
+    pub const PRIORITY: Interest = Interest(0b0100);
+
+    /// Interest in the peer hanging up.
+    pub const HUP: Interest = Interest(0b1000);
+
+    /// Returns `true` if the interest includes readability.
+    pub fn is_readable(self) -> bool {
+        self.contains(Interest::READABLE)
+    }
+
+    /// Returns `true` if the interest includes writability.
+    pub fn is_writable(self) -> bool {
+        self.contains(Interest::WRITABLE)
+    }
+
+    /// Returns `true` if the interest includes priority data.
+    pub fn is_priority(self) -> bool {
+        self.contains(Interest::PRIORITY)
+    }
+
+    /// Returns `true` if the interest includes hangups.
+    pub fn is_hup(self) -> bool {
+        self.contains(Interest::HUP)
+    }
+
+    fn contains(self, other: Interest) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Converts the interest into the readiness set the reactor registers for.
+    pub(crate) fn to_ready(self) -> Ready {
+        let mut ready = Ready::empty();
+        if self.is_readable() {
+            ready |= Ready::readable();
+        }
+        if self.is_writable() {
+            ready |= Ready::writable();
+        }
+        if self.is_priority() {
+            ready |= UnixReady::priority();
+        }
+        if self.is_hup() {
+            ready |= UnixReady::hup();
+        }
+        ready
+    }
+}
+
+impl BitOr for Interest {
+    type Output = Interest;
+
+    fn bitor(self, other: Interest) -> Interest {
+        Interest(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Interest {
+    fn bitor_assign(&mut self, other: Interest) {
+        self.0 |= other.0;
+    }
+}
+
+impl fmt::Debug for Interest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Interest::READABLE, "READABLE"),
+            (Interest::WRITABLE, "WRITABLE"),
+            (Interest::PRIORITY, "PRIORITY"),
+            (Interest::HUP, "HUP"),
+        ];
+
+        let mut first = true;
+        for &(interest, name) in names.iter() {
+            if self.contains(interest) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        if first {
+            f.write_str("(empty)")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the reactor reports readiness of an I/O handle.
+///
+/// The mode is chosen when the handle is registered with [`Async::with_interest`].
+///
+/// [`Async::with_interest`]: struct.Async.html#method.with_interest
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Mode {
+    /// Readiness is reported once whenever the handle transitions to ready.
+    ///
+    /// This is the default mode. Operations must be retried until they fail with
+    /// `WouldBlock` before waiting for readiness again.
+    Edge,
+
+    /// Readiness is reported for as long as the handle stays ready.
+    ///
+    /// To keep the reactor from spinning on a ready handle nobody is waiting on, the handle is
+    /// disarmed after each event and re-armed whenever a task starts waiting on it.
+    Level,
+
+    /// Readiness is reported once, after which the handle stays disarmed until
+    /// [`Async::rearm`] is called.
+    ///
+    /// [`Async::rearm`]: struct.Async.html#method.rearm
+    Oneshot,
+}
+
+impl Mode {
+    /// Converts the mode into the options the reactor registers with.
+    pub(crate) fn to_opts(self) -> PollOpt {
+        match self {
+            Mode::Edge => PollOpt::edge(),
+            Mode::Level => PollOpt::level() | PollOpt::oneshot(),
+            Mode::Oneshot => PollOpt::edge() | PollOpt::oneshot(),
+        }
+    }
+}
+
+impl Default for Mode {
+    fn default() -> Mode {
+        Mode::Edge
+    }
+}
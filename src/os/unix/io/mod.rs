@@ -1,5 +1,13 @@
 //! Unix-specific I/O extensions.
 
+cfg_unstable_default! {
+    pub use async_fd::{Async, ReadyGuard};
+    pub use interest::{Interest, Mode};
+
+    mod async_fd;
+    mod interest;
+}
+
 cfg_not_docs! {
     pub use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 }
@@ -3,6 +3,7 @@
 use std::fmt;
 use std::pin::Pin;
 use std::future::Future;
+use std::sync::Arc;
 
 use mio_uds;
 
@@ -107,7 +108,7 @@ impl UnixListener {
                 Some((io, addr)) => {
                     let mio_stream = mio_uds::UnixStream::from_stream(io)?;
                     let stream = UnixStream {
-                        watcher: Watcher::new(mio_stream),
+                        watcher: Arc::new(Watcher::new_stream(mio_stream)),
                     };
                     Poll::Ready(Ok((stream, addr)))
                 }
@@ -8,6 +8,12 @@ mod datagram;
 mod listener;
 mod stream;
 
+cfg_unstable! {
+    pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
+
+    mod split;
+}
+
 cfg_not_docs! {
     pub use std::os::unix::net::SocketAddr;
 }
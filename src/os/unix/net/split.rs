@@ -0,0 +1,239 @@
+use std::error::Error;
+use std::fmt;
+use std::net::Shutdown;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::{SocketAddr, UnixStream};
+use crate::io::{self, Read, Write};
+use crate::task::{Context, Poll};
+
+/// The reading half of a [`UnixStream`], borrowed with [`split`].
+///
+/// Reading and writing go through the reactor independently, so the halves can be used
+/// concurrently without any locking.
+///
+/// [`UnixStream`]: struct.UnixStream.html
+/// [`split`]: struct.UnixStream.html#method.split
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct ReadHalf<'a>(&'a UnixStream);
+
+/// The writing half of a [`UnixStream`], borrowed with [`split`].
+///
+/// Closing the half shuts down the write side of the connection.
+///
+/// [`UnixStream`]: struct.UnixStream.html
+/// [`split`]: struct.UnixStream.html#method.split
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct WriteHalf<'a>(&'a UnixStream);
+
+pub(super) fn split(stream: &UnixStream) -> (ReadHalf<'_>, WriteHalf<'_>) {
+    (ReadHalf(stream), WriteHalf(stream))
+}
+
+impl ReadHalf<'_> {
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.local_addr()
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.peer_addr()
+    }
+}
+
+impl WriteHalf<'_> {
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.local_addr()
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.peer_addr()
+    }
+}
+
+impl Read for ReadHalf<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self.0).poll_read(cx, buf)
+    }
+}
+
+impl Write for WriteHalf<'_> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self.0).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.0.shutdown(Shutdown::Write)?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The reading half of a [`UnixStream`], created by [`into_split`].
+///
+/// The half can be moved into another task, and put back together with the writing half using
+/// [`reunite`].
+///
+/// [`UnixStream`]: struct.UnixStream.html
+/// [`into_split`]: struct.UnixStream.html#method.into_split
+/// [`reunite`]: #method.reunite
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct OwnedReadHalf {
+    stream: UnixStream,
+}
+
+/// The writing half of a [`UnixStream`], created by [`into_split`].
+///
+/// By default, dropping the half shuts down the write side of the connection, which lets the
+/// peer know no more data is coming while the reading half stays open. This can be turned off
+/// with [`set_shutdown_on_drop`].
+///
+/// [`UnixStream`]: struct.UnixStream.html
+/// [`into_split`]: struct.UnixStream.html#method.into_split
+/// [`set_shutdown_on_drop`]: #method.set_shutdown_on_drop
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct OwnedWriteHalf {
+    stream: UnixStream,
+    shutdown_on_drop: bool,
+}
+
+pub(super) fn into_split(stream: UnixStream) -> (OwnedReadHalf, OwnedWriteHalf) {
+    let read = OwnedReadHalf {
+        stream: UnixStream {
+            watcher: stream.watcher.clone(),
+        },
+    };
+    let write = OwnedWriteHalf {
+        stream,
+        shutdown_on_drop: true,
+    };
+    (read, write)
+}
+
+impl OwnedReadHalf {
+    /// Puts the halves back together into the original stream.
+    ///
+    /// Returns an error containing both halves if they come from different streams.
+    pub fn reunite(self, other: OwnedWriteHalf) -> Result<UnixStream, ReuniteError> {
+        reunite(self, other)
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.local_addr()
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+}
+
+impl OwnedWriteHalf {
+    /// Puts the halves back together into the original stream.
+    ///
+    /// Returns an error containing both halves if they come from different streams.
+    pub fn reunite(self, other: OwnedReadHalf) -> Result<UnixStream, ReuniteError> {
+        reunite(other, self)
+    }
+
+    /// Sets whether dropping the half shuts down the write side of the connection.
+    ///
+    /// This is enabled by default.
+    pub fn set_shutdown_on_drop(&mut self, shutdown: bool) {
+        self.shutdown_on_drop = shutdown;
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.local_addr()
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+}
+
+fn reunite(read: OwnedReadHalf, mut write: OwnedWriteHalf) -> Result<UnixStream, ReuniteError> {
+    if Arc::ptr_eq(&read.stream.watcher, &write.stream.watcher) {
+        // The stream stays open, so the write side must not be shut down. Dropping the half
+        // right away leaves the returned stream as the only owner of the socket.
+        write.shutdown_on_drop = false;
+        drop(write);
+        Ok(read.stream)
+    } else {
+        Err(ReuniteError(read, write))
+    }
+}
+
+impl Drop for OwnedWriteHalf {
+    fn drop(&mut self) {
+        if self.shutdown_on_drop {
+            let _ = self.stream.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+impl Read for OwnedReadHalf {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.stream).poll_read(cx, buf)
+    }
+}
+
+impl Write for OwnedWriteHalf {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &self.stream).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.stream.shutdown(Shutdown::Write)?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The error returned when trying to reunite halves of different streams.
+///
+/// The error contains both halves, so that they aren't lost.
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct ReuniteError(pub OwnedReadHalf, pub OwnedWriteHalf);
+
+impl fmt::Display for ReuniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "tried to reunite halves of different streams".fmt(f)
+    }
+}
+
+impl Error for ReuniteError {}
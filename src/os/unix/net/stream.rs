@@ -4,9 +4,12 @@ use std::fmt;
 use std::io::{Read as _, Write as _};
 use std::net::Shutdown;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use mio_uds;
 
+#[cfg(feature = "unstable")]
+use super::split::{self, OwnedReadHalf, OwnedWriteHalf, ReadHalf, WriteHalf};
 use super::SocketAddr;
 use crate::io::{self, Read, Write};
 use crate::net::driver::Watcher;
@@ -38,7 +41,7 @@ use crate::task::{spawn_blocking, Context, Poll};
 /// # Ok(()) }) }
 /// ```
 pub struct UnixStream {
-    pub(super) watcher: Watcher<mio_uds::UnixStream>,
+    pub(super) watcher: Arc<Watcher<mio_uds::UnixStream>>,
 }
 
 impl UnixStream {
@@ -62,7 +65,7 @@ impl UnixStream {
             let std_stream = std::os::unix::net::UnixStream::connect(path)?;
             let mio_stream = mio_uds::UnixStream::from_stream(std_stream)?;
             Ok(UnixStream {
-                watcher: Watcher::new(mio_stream),
+                watcher: Arc::new(Watcher::new_stream(mio_stream)),
             })
         })
         .await
@@ -86,10 +89,10 @@ impl UnixStream {
     pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
         let (a, b) = mio_uds::UnixStream::pair()?;
         let a = UnixStream {
-            watcher: Watcher::new(a),
+            watcher: Arc::new(Watcher::new_stream(a)),
         };
         let b = UnixStream {
-            watcher: Watcher::new(b),
+            watcher: Arc::new(Watcher::new_stream(b)),
         };
         Ok((a, b))
     }
@@ -151,6 +154,74 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.watcher.get_ref().shutdown(how)
     }
+
+    /// Splits the stream into a reading half and a writing half borrowed from it.
+    ///
+    /// The halves can be used concurrently, for example by joining a future that reads with a
+    /// future that writes. They don't share any lock.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::io;
+    /// use async_std::os::unix::net::UnixStream;
+    ///
+    /// let mut stream = UnixStream::connect("/tmp/socket").await?;
+    /// let (mut reader, mut writer) = stream.split();
+    ///
+    /// // Echo everything back to the peer.
+    /// io::copy(&mut reader, &mut writer).await?;
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn split(&mut self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        split::split(self)
+    }
+
+    /// Splits the stream into a reading half and a writing half that can be moved into
+    /// different tasks.
+    ///
+    /// The halves don't share any lock, and can be put back together with [`reunite`]. Dropping
+    /// the writing half shuts down the write side of the connection, unless disabled with
+    /// [`set_shutdown_on_drop`].
+    ///
+    /// [`reunite`]: struct.OwnedReadHalf.html#method.reunite
+    /// [`set_shutdown_on_drop`]: struct.OwnedWriteHalf.html#method.set_shutdown_on_drop
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// use async_std::os::unix::net::UnixStream;
+    /// use async_std::prelude::*;
+    /// use async_std::task;
+    ///
+    /// let stream = UnixStream::connect("/tmp/socket").await?;
+    /// let (mut reader, mut writer) = stream.into_split();
+    ///
+    /// let handle = task::spawn(async move {
+    ///     writer.write_all(b"hello").await?;
+    ///     Ok::<_, std::io::Error>(writer)
+    /// });
+    ///
+    /// let mut buf = vec![0u8; 1024];
+    /// let n = reader.read(&mut buf).await?;
+    ///
+    /// let writer = handle.await?;
+    /// let stream = reader.reunite(writer).unwrap();
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        split::into_split(self)
+    }
 }
 
 impl Read for UnixStream {
@@ -169,7 +240,8 @@ impl Read for &UnixStream {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        self.watcher.poll_read_with(cx, |mut inner| inner.read(buf))
+        self.watcher
+            .poll_read_buf(cx, buf, |mut inner, buf| inner.read(buf))
     }
 }
 
@@ -198,7 +270,7 @@ impl Write for &UnixStream {
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
         self.watcher
-            .poll_write_with(cx, |mut inner| inner.write(buf))
+            .poll_write_buf(cx, buf, |mut inner, buf| inner.write(buf))
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -232,7 +304,7 @@ impl From<std::os::unix::net::UnixStream> for UnixStream {
     fn from(stream: std::os::unix::net::UnixStream) -> UnixStream {
         let mio_stream = mio_uds::UnixStream::from_stream(stream).unwrap();
         UnixStream {
-            watcher: Watcher::new(mio_stream),
+            watcher: Arc::new(Watcher::new(mio_stream)),
         }
     }
 }
@@ -252,6 +324,10 @@ impl FromRawFd for UnixStream {
 
 impl IntoRawFd for UnixStream {
     fn into_raw_fd(self) -> RawFd {
-        self.watcher.into_inner().into_raw_fd()
+        // The watcher is only shared between the owned halves, which have been dropped by now.
+        match Arc::try_unwrap(self.watcher) {
+            Ok(watcher) => watcher.into_inner().into_raw_fd(),
+            Err(_) => unreachable!("the stream is still shared"),
+        }
     }
 }
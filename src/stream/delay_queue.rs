@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use slab::Slab;
+
+use crate::stream::Stream;
+use crate::task::{budget, Context, Poll};
+use crate::timer::{self, Timer};
+
+/// A queue of items that are yielded once their individual deadlines expire.
+///
+/// Inserting an item returns a [`DelayQueueKey`], which can be used to move the item's deadline
+/// with [`reset`] or to take the item out of the queue with [`remove`]. The queue is a [`Stream`]
+/// that yields items in the order their deadlines expire, so a single task can track thousands
+/// of expirations, such as session timeouts or retry schedules, with a single timer.
+///
+/// The stream ends when the queue is empty, but it can be polled again after inserting more
+/// items.
+///
+/// [`DelayQueueKey`]: struct.DelayQueueKey.html
+/// [`reset`]: #method.reset
+/// [`remove`]: #method.remove
+/// [`Stream`]: trait.Stream.html
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use std::time::Duration;
+///
+/// use async_std::prelude::*;
+/// use async_std::stream::DelayQueue;
+///
+/// let mut queue = DelayQueue::new();
+/// queue.insert("slow", Duration::from_millis(20));
+/// let fast = queue.insert("fast", Duration::from_millis(30));
+/// let gone = queue.insert("gone", Duration::from_millis(10));
+///
+/// queue.reset(&fast, Duration::from_millis(5));
+/// assert_eq!(queue.remove(&gone), Some("gone"));
+///
+/// assert_eq!(queue.next().await, Some("fast"));
+/// assert_eq!(queue.next().await, Some("slow"));
+/// assert_eq!(queue.next().await, None);
+/// #
+/// # })
+/// ```
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub struct DelayQueue<T> {
+    /// The items in the queue.
+    items: Slab<Item<T>>,
+
+    /// Keys of the items, ordered by their deadlines and then by insertion order.
+    expirations: BTreeMap<(Instant, u64), usize>,
+
+    /// The identifier of the next inserted item.
+    next_id: u64,
+
+    /// The timer firing at the earliest deadline, created on first poll.
+    timer: Option<Timer>,
+}
+
+/// A key identifying an item in a [`DelayQueue`].
+///
+/// The key becomes stale once the item expires or is removed, and stale keys are ignored.
+///
+/// [`DelayQueue`]: struct.DelayQueue.html
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DelayQueueKey {
+    index: usize,
+    id: u64,
+}
+
+/// An item stored in a delay queue.
+struct Item<T> {
+    value: T,
+    deadline: Instant,
+    id: u64,
+}
+
+impl<T> DelayQueue<T> {
+    /// Creates an empty queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_std::stream::DelayQueue;
+    ///
+    /// let queue: DelayQueue<i32> = DelayQueue::new();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn new() -> DelayQueue<T> {
+        DelayQueue {
+            items: Slab::new(),
+            expirations: BTreeMap::new(),
+            next_id: 0,
+            timer: None,
+        }
+    }
+
+    /// Inserts an item that expires after the given duration.
+    pub fn insert(&mut self, value: T, dur: Duration) -> DelayQueueKey {
+        self.insert_at(value, timer::now() + dur)
+    }
+
+    /// Inserts an item that expires at the given point in time.
+    pub fn insert_at(&mut self, value: T, deadline: Instant) -> DelayQueueKey {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let index = self.items.insert(Item {
+            value,
+            deadline,
+            id,
+        });
+        self.expirations.insert((deadline, id), index);
+        DelayQueueKey { index, id }
+    }
+
+    /// Changes the deadline of an item to expire after the given duration.
+    ///
+    /// Returns `false` if the item has already expired or has been removed.
+    pub fn reset(&mut self, key: &DelayQueueKey, dur: Duration) -> bool {
+        self.reset_at(key, timer::now() + dur)
+    }
+
+    /// Changes the deadline of an item to expire at the given point in time.
+    ///
+    /// Returns `false` if the item has already expired or has been removed.
+    pub fn reset_at(&mut self, key: &DelayQueueKey, deadline: Instant) -> bool {
+        let item = match self.items.get_mut(key.index) {
+            Some(item) if item.id == key.id => item,
+            _ => return false,
+        };
+
+        self.expirations.remove(&(item.deadline, item.id));
+        item.deadline = deadline;
+        self.expirations.insert((deadline, item.id), key.index);
+        true
+    }
+
+    /// Removes an item from the queue.
+    ///
+    /// Returns `None` if the item has already expired or has been removed.
+    pub fn remove(&mut self, key: &DelayQueueKey) -> Option<T> {
+        match self.items.get(key.index) {
+            Some(item) if item.id == key.id => {}
+            _ => return None,
+        }
+
+        let item = self.items.remove(key.index);
+        self.expirations.remove(&(item.deadline, item.id));
+        Some(item.value)
+    }
+
+    /// Returns the deadline of an item, or `None` if it has expired or has been removed.
+    pub fn deadline(&self, key: &DelayQueueKey) -> Option<Instant> {
+        match self.items.get(key.index) {
+            Some(item) if item.id == key.id => Some(item.deadline),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Removes all items from the queue.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.expirations.clear();
+    }
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> DelayQueue<T> {
+        DelayQueue::new()
+    }
+}
+
+// The items are never pinned.
+impl<T> Unpin for DelayQueue<T> {}
+
+impl<T> Stream for DelayQueue<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        futures_core::ready!(budget::poll_proceed(cx));
+
+        let this = self.get_mut();
+        loop {
+            let (deadline, id, index) = match this.expirations.iter().next() {
+                Some((&(deadline, id), &index)) => (deadline, id, index),
+                None => return Poll::Ready(None),
+            };
+
+            // Yield the earliest item if it has expired.
+            if deadline <= timer::now() {
+                this.expirations.remove(&(deadline, id));
+                let item = this.items.remove(index);
+                return Poll::Ready(Some(item.value));
+            }
+
+            // Otherwise, wait until it expires.
+            let timer = this.timer.get_or_insert_with(|| Timer::at(deadline));
+            if timer.deadline() != deadline {
+                timer.reset(deadline);
+            }
+            futures_core::ready!(Pin::new(timer).poll(cx));
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DelayQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.expirations
+                    .values()
+                    .map(|&index| (&self.items[index].value, self.items[index].deadline)),
+            )
+            .finish()
+    }
+}
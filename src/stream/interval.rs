@@ -4,24 +4,33 @@ use std::time::{Duration, Instant};
 
 use crate::future::Future;
 use crate::stream::Stream;
-use futures_timer::Delay;
+use crate::task::budget;
+use crate::timer::{self, Timer};
 
 /// Creates a new stream that yields at a set interval.
 ///
 /// The stream first yields after `dur`, and continues to yield every
 /// `dur` after that. The stream accounts for time elapsed between calls, and
-/// will adjust accordingly to prevent time skews.
+/// will adjust accordingly to prevent time skews. Ticks that are missed because
+/// the stream wasn't polled in time are handled according to the
+/// [`MissedTickBehavior`], which skips them by default.
 ///
-/// Each interval may be slightly longer than the specified duration, but never
-/// less.
+/// Each item is the instant the tick was scheduled at. Each interval may be
+/// slightly longer than the specified duration, but never less.
 ///
 /// Note that intervals are not intended for high resolution timers, but rather
 /// they will likely fire some granularity after the exact instant that they're
 /// otherwise indicated to fire at.
 ///
-/// See also: [`task::sleep`].
+/// See also: [`task::sleep`], [`interval_at`].
 ///
+/// [`MissedTickBehavior`]: enum.MissedTickBehavior.html
 /// [`task::sleep`]: ../task/fn.sleep.html
+/// [`interval_at`]: fn.interval_at.html
+///
+/// # Panics
+///
+/// This function panics if `dur` is zero.
 ///
 /// # Examples
 ///
@@ -44,37 +53,178 @@ use futures_timer::Delay;
 #[cfg(feature = "unstable")]
 #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
 pub fn interval(dur: Duration) -> Interval {
+    interval_at(timer::now() + dur, dur)
+}
+
+/// Creates a new stream that yields at a set interval, starting at a point in time.
+///
+/// The stream first yields at `start`, and continues to yield every `period`
+/// after that. If `start` has already passed, the first tick fires immediately.
+///
+/// # Panics
+///
+/// This function panics if `period` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use async_std::prelude::*;
+/// use async_std::stream;
+/// use std::time::{Duration, Instant};
+///
+/// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+/// #
+/// let start = Instant::now();
+/// let period = Duration::from_millis(10);
+/// let mut interval = stream::interval_at(start, period);
+///
+/// assert_eq!(interval.next().await, Some(start));
+/// assert_eq!(interval.next().await, Some(start + period));
+/// #
+/// # Ok(()) }) }
+/// ```
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(
+        period > Duration::from_secs(0),
+        "the period must be non-zero"
+    );
+
     Interval {
-        delay: Delay::new(dur),
-        interval: dur,
+        delay: Timer::at(start),
+        period,
+        missed_tick_behavior: MissedTickBehavior::default(),
+    }
+}
+
+/// Defines how an [`Interval`] handles ticks missed because it wasn't polled in time.
+///
+/// A tick is missed when the stream is polled so late that the following tick
+/// is already due as well.
+///
+/// [`Interval`]: struct.Interval.html
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MissedTickBehavior {
+    /// Yields the missed ticks as fast as possible until the stream has caught up.
+    ///
+    /// The ticks keep their original schedule, so the number of ticks over a long
+    /// time is exact.
+    Burst,
+
+    /// Yields the missed tick, and schedules the following ticks a full period
+    /// after it was yielded.
+    ///
+    /// The schedule shifts by the time the stream lagged behind.
+    Delay,
+
+    /// Yields the missed tick, and skips the ticks that are due as well.
+    ///
+    /// The following ticks stay aligned to the original schedule. This is the
+    /// default.
+    Skip,
+}
+
+impl Default for MissedTickBehavior {
+    fn default() -> MissedTickBehavior {
+        MissedTickBehavior::Skip
     }
 }
 
 /// A stream representing notifications at fixed interval
 ///
-/// This stream is created by the [`interval`] function. See its
-/// documentation for more.
+/// This stream is created by the [`interval`] and [`interval_at`] functions.
+/// See their documentation for more.
 ///
 /// [`interval`]: fn.interval.html
+/// [`interval_at`]: fn.interval_at.html
 #[cfg(feature = "unstable")]
 #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
 #[derive(Debug)]
 pub struct Interval {
-    delay: Delay,
-    interval: Duration,
+    delay: Timer,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+impl Interval {
+    /// Returns the period of the interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns how missed ticks are handled.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Configures how missed ticks are handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_std::stream::{self, MissedTickBehavior};
+    /// use std::time::Duration;
+    ///
+    /// let mut interval = stream::interval(Duration::from_secs(1));
+    /// interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    /// assert_eq!(interval.missed_tick_behavior(), MissedTickBehavior::Burst);
+    /// ```
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Restarts the interval, so that the next tick fires a full period from now.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_std::prelude::*;
+    /// use async_std::stream;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// # fn main() -> std::io::Result<()> { async_std::task::block_on(async {
+    /// #
+    /// let period = Duration::from_millis(10);
+    /// let mut interval = stream::interval_at(Instant::now(), period);
+    ///
+    /// let reset = Instant::now();
+    /// interval.reset();
+    /// assert!(interval.next().await.unwrap() >= reset + period);
+    /// #
+    /// # Ok(()) }) }
+    /// ```
+    pub fn reset(&mut self) {
+        self.delay.reset(timer::now() + self.period);
+    }
 }
 
 impl Stream for Interval {
-    type Item = ();
+    type Item = Instant;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        futures_core::ready!(budget::poll_proceed(cx));
+
         if Pin::new(&mut self.delay).poll(cx).is_pending() {
             return Poll::Pending;
         }
-        let when = Instant::now();
-        let next = next_interval(when, Instant::now(), self.interval);
+
+        let tick = self.delay.deadline();
+        let now = timer::now();
+        let next = if tick + self.period > now {
+            tick + self.period
+        } else {
+            // The next tick is due already, so it has been missed.
+            match self.missed_tick_behavior {
+                MissedTickBehavior::Burst => tick + self.period,
+                MissedTickBehavior::Delay => now + self.period,
+                MissedTickBehavior::Skip => next_interval(tick, now, self.period),
+            }
+        };
         self.delay.reset(next);
-        Poll::Ready(Some(()))
+        Poll::Ready(Some(tick))
     }
 }
 
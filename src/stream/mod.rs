@@ -318,6 +318,7 @@ mod repeat;
 mod repeat_with;
 
 cfg_unstable! {
+    mod delay_queue;
     mod double_ended_stream;
     mod exact_size_stream;
     mod extend;
@@ -330,12 +331,13 @@ cfg_unstable! {
     mod successors;
     mod sum;
 
+    pub use delay_queue::{DelayQueue, DelayQueueKey};
     pub use double_ended_stream::DoubleEndedStream;
     pub use exact_size_stream::ExactSizeStream;
     pub use extend::{extend, Extend};
     pub use from_stream::FromStream;
     pub use fused_stream::FusedStream;
-    pub use interval::{interval, Interval};
+    pub use interval::{interval, interval_at, Interval, MissedTickBehavior};
     pub use into_stream::IntoStream;
     pub use pending::{pending, Pending};
     pub use product::Product;
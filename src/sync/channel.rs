@@ -16,6 +16,7 @@ use crossbeam_utils::Backoff;
 
 use crate::stream::Stream;
 use crate::sync::WakerSet;
+use crate::task::budget;
 
 /// Creates a bounded multi-producer multi-consumer channel.
 ///
@@ -148,6 +149,8 @@ impl<T> Sender<T> {
             type Output = ();
 
             fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                futures_core::ready!(budget::poll_proceed(cx));
+
                 loop {
                     let msg = self.msg.take().unwrap();
 
@@ -601,6 +604,8 @@ fn poll_recv<T>(
     opt_key: &mut Option<usize>,
     cx: &mut Context<'_>,
 ) -> Poll<Result<T, RecvError>> {
+    futures_core::ready!(budget::poll_proceed(cx));
+
     loop {
         // If the current task is in the set, remove it.
         if let Some(key) = opt_key.take() {
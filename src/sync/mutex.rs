@@ -6,7 +6,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::future::Future;
 
 use crate::sync::WakerSet;
-use crate::task::{Context, Poll};
+use crate::task::{budget, Context, Poll};
 
 /// A mutual exclusion primitive for protecting shared data.
 ///
@@ -102,6 +102,8 @@ impl<T: ?Sized> Mutex<T> {
             type Output = MutexGuard<'a, T>;
 
             fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                futures_core::ready!(budget::poll_proceed(cx));
+
                 loop {
                     // If the current task is in the set, remove it.
                     if let Some(key) = self.opt_key.take() {
@@ -8,7 +8,7 @@ use std::future::Future;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::sync::WakerSet;
-use crate::task::{Context, Poll};
+use crate::task::{budget, Context, Poll};
 
 /// Set if a write lock is held.
 #[allow(clippy::identity_op)]
@@ -110,6 +110,8 @@ impl<T: ?Sized> RwLock<T> {
             type Output = RwLockReadGuard<'a, T>;
 
             fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                futures_core::ready!(budget::poll_proceed(cx));
+
                 loop {
                     // If the current task is in the set, remove it.
                     if let Some(key) = self.opt_key.take() {
@@ -235,6 +237,8 @@ impl<T: ?Sized> RwLock<T> {
             type Output = RwLockWriteGuard<'a, T>;
 
             fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                futures_core::ready!(budget::poll_proceed(cx));
+
                 loop {
                     // If the current task is in the set, remove it.
                     if let Some(key) = self.opt_key.take() {
@@ -0,0 +1,44 @@
+use crate::task::executor;
+
+/// Runs a blocking function on the current thread without starving other tasks.
+///
+/// Unlike [`spawn_blocking`], this function borrows its closure for the duration of the call, so
+/// the closure doesn't have to be `'static`.
+///
+/// When called from a worker thread of an executor, the thread's queue of pending tasks is handed
+/// off to a newly started worker thread before `f` runs, so other tasks keep making progress while
+/// the current thread is blocked. Once the current task yields, the blocked thread exits and the
+/// new thread takes its place. When called from any other thread, `f` simply runs inline.
+///
+/// Other tasks cannot run on the current thread while `f` runs, including tasks spawned with
+/// [`spawn_local`] and the futures passed to [`block_on`], so those are blocked just like with a
+/// direct blocking call.
+///
+/// [`spawn_blocking`]: fn.spawn_blocking.html
+/// [`spawn_local`]: fn.spawn_local.html
+/// [`block_on`]: fn.block_on.html
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use async_std::task;
+///
+/// let mut data = vec![3, 1, 2];
+///
+/// task::spawn(async move {
+///     // Sort a borrowed vector using a blocking call.
+///     task::block_in_place(|| data.sort());
+///     assert_eq!(data, [1, 2, 3]);
+/// })
+/// .await;
+/// #
+/// # })
+/// ```
+pub fn block_in_place<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    executor::block_in_place(f)
+}
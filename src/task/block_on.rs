@@ -4,7 +4,7 @@ use std::mem::{self, ManuallyDrop};
 use std::sync::Arc;
 use std::task::{RawWaker, RawWakerVTable};
 
-use crossbeam_utils::sync::Parker;
+use crossbeam_utils::sync::{Parker, Unparker};
 use kv_log_macro::trace;
 use log::log_enabled;
 
@@ -36,9 +36,20 @@ use crate::task::{Context, Poll, Task, Waker};
 pub fn block_on<F, T>(future: F) -> T
 where
     F: Future<Output = T>,
+{
+    block_on_with(future, |_| false)
+}
+
+/// Spawns a task and blocks the current thread on its result.
+///
+/// The `tick` function is invoked between polls of the future, see `run`.
+pub(crate) fn block_on_with<F, T, G>(future: F, tick: G) -> T
+where
+    F: Future<Output = T>,
+    G: FnMut(&Unparker) -> bool,
 {
     // Create a new task handle.
-    let task = Task::new(None);
+    let task = Task::new(None, None);
 
     // Log this `block_on` operation.
     if log_enabled!(log::Level::Trace) {
@@ -69,13 +80,18 @@ where
     };
 
     // Run the future as a task.
-    unsafe { Task::set_current(&task, || run(future)) }
+    unsafe { Task::set_current(&task, || run(future, tick)) }
 }
 
 /// Blocks the current thread on a future's result.
-fn run<F, T>(future: F) -> T
+///
+/// Every time the future returns `Poll::Pending`, `tick` is invoked with the `Unparker` that wakes
+/// up the current thread. If it returns `true`, the future is polled again right away. Otherwise,
+/// the thread is parked until the future's waker or the `Unparker` is used.
+fn run<F, T, G>(future: F, mut tick: G) -> T
 where
     F: Future<Output = T>,
+    G: FnMut(&Unparker) -> bool,
 {
     thread_local! {
         // May hold a pre-allocated parker that can be reused for efficiency.
@@ -131,7 +147,9 @@ where
                 return t;
             }
 
-            arc_parker.park();
+            if !tick(arc_parker.unparker()) {
+                arc_parker.park();
+            }
         }
     })
 }
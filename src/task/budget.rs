@@ -0,0 +1,81 @@
+//! Cooperative scheduling budget.
+//!
+//! A task that keeps polling resources that are always ready never yields, which starves other
+//! tasks running on the same thread. To prevent that, every task gets a budget of operations each
+//! time it is polled. Leaf resources like sockets, channels, mutexes, and timers consume a unit of
+//! the budget before doing any work. Once the budget is used up, they return `Poll::Pending` and
+//! wake the task right away, forcing it to yield back to the executor.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+
+use pin_project_lite::pin_project;
+
+use crate::task::{Context, Poll};
+
+/// The default number of operations a task may perform each time it is polled.
+#[cfg_attr(not(feature = "default"), allow(dead_code))]
+pub(crate) const DEFAULT_BUDGET: u32 = 128;
+
+thread_local! {
+    /// The remaining budget of the task running on the current thread.
+    ///
+    /// This is `None` if the task is unconstrained or no task is running.
+    static BUDGET: Cell<Option<u32>> = Cell::new(None);
+}
+
+/// Consumes a unit of the current task's budget.
+///
+/// If the budget is used up, the task is woken up and `Poll::Pending` is returned.
+pub(crate) fn poll_proceed(cx: &mut Context<'_>) -> Poll<()> {
+    let res = BUDGET.try_with(|budget| match budget.get() {
+        None => Poll::Ready(()),
+        Some(0) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+        Some(n) => {
+            budget.set(Some(n - 1));
+            Poll::Ready(())
+        }
+    });
+    res.unwrap_or(Poll::Ready(()))
+}
+
+pin_project! {
+    /// A future that resets the budget every time it is polled.
+    pub(crate) struct WithBudget<F> {
+        #[pin]
+        future: F,
+        budget: Option<u32>,
+    }
+}
+
+#[cfg_attr(not(feature = "default"), allow(dead_code))]
+impl<F> WithBudget<F> {
+    /// Wraps a future so that it runs with the given budget, or unconstrained if `None`.
+    pub fn new(future: F, budget: Option<u32>) -> WithBudget<F> {
+        WithBudget { future, budget }
+    }
+}
+
+impl<F: Future> Future for WithBudget<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let _reset = Reset(BUDGET.with(|b| b.replace(*this.budget)));
+        this.future.poll(cx)
+    }
+}
+
+/// Restores the previous budget when dropped, even if polling panics.
+struct Reset(Option<u32>);
+
+impl Drop for Reset {
+    fn drop(&mut self) {
+        let _ = BUDGET.try_with(|b| b.set(self.0));
+    }
+}
@@ -1,23 +1,48 @@
 use kv_log_macro::trace;
 use log::log_enabled;
 use std::future::Future;
+use std::ops::Deref;
+use std::panic::Location;
+#[cfg(feature = "unstable")]
+use std::sync::Arc;
 
 use crate::io;
-use crate::task::executor;
-use crate::task::{JoinHandle, Task};
+use crate::task::budget::{self, WithBudget};
+use crate::task::executor::{self, Pool};
+#[cfg(feature = "unstable")]
+use crate::task::local_executor::LocalQueue;
+use crate::task::panic::{CatchUnwind, Panic};
+use crate::task::registry::{Record, Registration};
+#[cfg(feature = "unstable")]
+use crate::task::simulation::SimQueue;
+#[cfg(feature = "unstable")]
+use crate::task::{Handle, TaskGroup};
+use crate::task::{JoinHandle, Priority, Task, TaskId};
 use crate::utils::abort_on_panic;
 
 /// Task builder that configures the settings of a new task.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Builder {
     pub(crate) name: Option<String>,
+    pub(crate) budget: Option<u32>,
+    pub(crate) priority: Priority,
 }
 
 impl Builder {
     /// Creates a new builder.
     #[inline]
     pub fn new() -> Builder {
-        Builder { name: None }
+        Builder {
+            name: None,
+            // Tasks can only opt out of the budget with the unstable `budget` method, so they
+            // are unconstrained unless it is available.
+            budget: if cfg!(feature = "unstable") {
+                Some(budget::DEFAULT_BUDGET)
+            } else {
+                None
+            },
+            priority: Priority::Normal,
+        }
     }
 
     /// Configures the name of the task.
@@ -27,14 +52,287 @@ impl Builder {
         self
     }
 
+    /// Configures the cooperative scheduling budget of the task.
+    ///
+    /// Each time the task is polled, it may perform up to `budget` operations on resources like
+    /// sockets, channels, mutexes, and timers. Once the budget is used up, these resources return
+    /// `Poll::Pending` and immediately wake the task, so that it yields to other tasks even if the
+    /// resources are always ready.
+    ///
+    /// Passing `None` makes the task unconstrained. The default budget is 128 operations.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the budget is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::task;
+    ///
+    /// let handle = task::Builder::new()
+    ///     .budget(Some(32))
+    ///     .spawn(async { 1 + 2 })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(handle.await, 3);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn budget(mut self, budget: Option<u32>) -> Builder {
+        assert_ne!(budget, Some(0), "the budget must be positive");
+        self.budget = budget;
+        self
+    }
+
+    /// Configures the scheduling priority of the task.
+    ///
+    /// Worker threads always run tasks of higher priority first. The priority only applies to
+    /// tasks spawned onto an executor, and is ignored by [`spawn_local`] and simulations.
+    ///
+    /// The default priority is [`Priority::Normal`].
+    ///
+    /// [`spawn_local`]: #method.spawn_local
+    /// [`Priority::Normal`]: enum.Priority.html#variant.Normal
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::task::{self, Priority};
+    ///
+    /// let handle = task::Builder::new()
+    ///     .priority(Priority::High)
+    ///     .spawn(async { 1 + 2 })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(handle.await, 3);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn priority(mut self, priority: Priority) -> Builder {
+        self.priority = priority;
+        self
+    }
+
     /// Spawns a task with the configured settings.
+    #[track_caller]
     pub fn spawn<F, T>(self, future: F) -> io::Result<JoinHandle<T>>
     where
         F: Future<Output = T> + Send + 'static,
         T: Send + 'static,
     {
-        // Create a new task handle.
-        let task = Task::new(self.name);
+        // Within a simulation, tasks run on the simulation's thread.
+        #[cfg(feature = "unstable")]
+        {
+            if let Some(queue) = SimQueue::current() {
+                return Ok(self.spawn_simulated(queue, future));
+            }
+        }
+
+        self.spawn_with(future, executor::pool())
+    }
+
+    /// Spawns a task with the configured settings onto a specific executor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_std::task::{self, Executor};
+    ///
+    /// let executor = Executor::new().unwrap();
+    ///
+    /// let handle = task::Builder::new()
+    ///     .name("child".to_string())
+    ///     .spawn_on(&executor.handle(), async { 1 + 2 })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(task::block_on(handle), 3);
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    #[track_caller]
+    pub fn spawn_on<F, T>(self, handle: &Handle, future: F) -> io::Result<JoinHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.spawn_with(future, handle.pool().clone())
+    }
+
+    /// Spawns a task with the configured settings into a task group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::task::{self, TaskGroup};
+    ///
+    /// let mut group = TaskGroup::new();
+    /// task::Builder::new()
+    ///     .name("child".to_string())
+    ///     .spawn_in(&mut group, async { task::current().name().map(String::from) })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(group.join().await.unwrap(), vec![Some("child".to_string())]);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    #[track_caller]
+    pub fn spawn_in<F, T>(self, group: &mut TaskGroup<T>, future: F) -> io::Result<()>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        group.spawn_with(self, future)
+    }
+
+    /// Spawns a `!Send` task with the configured settings onto the current thread.
+    ///
+    /// This method returns an error if it is not called within [`LocalExecutor::run`].
+    ///
+    /// [`LocalExecutor::run`]: struct.LocalExecutor.html#method.run
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// use async_std::task::{self, LocalExecutor};
+    ///
+    /// let executor = LocalExecutor::new();
+    ///
+    /// let val = executor.run(async {
+    ///     let rc = Rc::new(3);
+    ///     task::Builder::new()
+    ///         .name("local".to_string())
+    ///         .spawn_local(async move { *rc })
+    ///         .unwrap()
+    ///         .await
+    /// });
+    /// assert_eq!(val, 3);
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    #[track_caller]
+    pub fn spawn_local<F, T>(self, future: F) -> io::Result<JoinHandle<T>>
+    where
+        F: Future<Output = T> + 'static,
+        T: 'static,
+    {
+        let queue = LocalQueue::current().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "`spawn_local` called outside the context of a local executor",
+            )
+        })?;
+        Ok(self.spawn_local_on(queue, future))
+    }
+
+    /// Spawns a `!Send` task onto the given local queue.
+    #[cfg(feature = "unstable")]
+    #[track_caller]
+    pub(crate) fn spawn_local_on<F, T>(self, queue: Arc<LocalQueue>, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + 'static,
+        T: 'static,
+    {
+        let (task, future) = self.build(future);
+
+        // Keep track of the tasks so that idle ones can be cancelled when the executor is dropped.
+        let id = task.id();
+        let completed = LocalCompleted {
+            queue: queue.clone(),
+            id,
+        };
+        let future = async move {
+            let _completed = completed;
+            future.await
+        };
+
+        let scheduler = queue.clone();
+        let schedule = move |t| scheduler.schedule(Runnable::new(t));
+        let (task, handle) = async_task::spawn_local(future, schedule, task);
+        queue.task_spawned(id, task.waker());
+        task.schedule();
+        JoinHandle::new(handle)
+    }
+
+    /// Spawns a task onto the given simulation queue.
+    #[cfg(feature = "unstable")]
+    #[track_caller]
+    pub(crate) fn spawn_simulated<F, T>(self, queue: Arc<SimQueue>, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (task, future) = self.build(future);
+
+        let schedule = move |t| queue.schedule(Runnable::new(t));
+        let (task, handle) = async_task::spawn(future, schedule, task);
+        task.schedule();
+        JoinHandle::new(handle)
+    }
+
+    /// Spawns a task onto the given executor.
+    #[track_caller]
+    fn spawn_with<F, T, P>(self, future: F, pool: P) -> io::Result<JoinHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+        P: Deref<Target = Pool> + Clone + Send + Sync + 'static,
+    {
+        if !pool.accepts_spawns() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the executor has been shut down",
+            ));
+        }
+
+        let priority = self.priority;
+        let (task, future) = self.build(future);
+
+        // Keep track of the tasks running on the executor. The guard is moved into the future,
+        // so it also runs if the task is cancelled before it is polled for the first time.
+        let id = task.id();
+        let completed = Completed {
+            pool: pool.clone(),
+            id,
+        };
+        let future = async move {
+            let _completed = completed;
+            future.await
+        };
+
+        let scheduler = pool.clone();
+        let schedule = move |t| scheduler.schedule(Runnable::new(t), priority);
+        let (task, handle) = async_task::spawn(future, schedule, task);
+        pool.task_spawned(id, task.waker());
+        task.schedule();
+        Ok(JoinHandle::new(handle))
+    }
+
+    /// Creates a task handle and wraps the future so that it runs as that task.
+    #[track_caller]
+    fn build<F, T>(self, future: F) -> (Task, impl Future<Output = Result<T, Panic>>)
+    where
+        F: Future<Output = T>,
+    {
+        let budget = self.budget;
+
+        // Create a new task handle, recording it in the task registry if enabled.
+        let task = Task::new(self.name, Record::new(Location::caller()));
+        let registration = Registration::new(&task);
 
         // Log this `spawn` operation.
         if log_enabled!(log::Level::Trace) {
@@ -45,6 +343,9 @@ impl Builder {
         }
 
         let future = async move {
+            // Remove the task from the registry on exit.
+            let _registration = registration;
+
             // Drop task-locals on exit.
             defer! {
                 Task::get_current(|t| unsafe { t.drop_locals() });
@@ -64,10 +365,41 @@ impl Builder {
             future.await
         };
 
-        let schedule = move |t| executor::schedule(Runnable(t));
-        let (task, handle) = async_task::spawn(future, schedule, task);
-        task.schedule();
-        Ok(JoinHandle::new(handle))
+        // Reset the budget every time the task is polled, and catch panics so that they can be
+        // propagated through the `JoinHandle`.
+        (task, CatchUnwind::new(WithBudget::new(future, budget)))
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+/// Records that a task spawned onto an executor has completed when its future is dropped.
+struct Completed<P: Deref<Target = Pool>> {
+    pool: P,
+    id: TaskId,
+}
+
+impl<P: Deref<Target = Pool>> Drop for Completed<P> {
+    fn drop(&mut self) {
+        self.pool.task_completed(self.id);
+    }
+}
+
+/// Records that a task spawned onto a local executor has completed when its future is dropped.
+#[cfg(feature = "unstable")]
+struct LocalCompleted {
+    queue: Arc<LocalQueue>,
+    id: TaskId,
+}
+
+#[cfg(feature = "unstable")]
+impl Drop for LocalCompleted {
+    fn drop(&mut self) {
+        self.queue.task_completed(self.id);
     }
 }
 
@@ -75,10 +407,30 @@ impl Builder {
 pub(crate) struct Runnable(async_task::Task<Task>);
 
 impl Runnable {
+    /// Wraps a task that has just been scheduled.
+    fn new(task: async_task::Task<Task>) -> Runnable {
+        if let Some(record) = task.tag().record() {
+            record.scheduled();
+        }
+        Runnable(task)
+    }
+
     /// Runs the task by polling its future once.
     pub fn run(self) {
+        // The task may be deallocated by the time it returns, so hold onto its record.
+        let record = self.0.tag().record().cloned();
+        if let Some(record) = &record {
+            record.running();
+        }
+
+        // Panics in the task's future are caught and reported through its `JoinHandle`, so
+        // anything unwinding here is a bug in the runtime itself.
         unsafe {
             Task::set_current(self.0.tag(), || abort_on_panic(|| self.0.run()));
         }
+
+        if let Some(record) = record {
+            record.idle();
+        }
     }
 }
@@ -0,0 +1,75 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A callback invoked by worker threads.
+pub(crate) type Hook = Arc<dyn Fn() + Send + Sync>;
+
+/// Configuration of an executor's worker threads.
+///
+/// The settings of the blocking pool only take effect in the configuration of the global executor,
+/// since the blocking pool is shared by all executors.
+#[derive(Clone)]
+pub(crate) struct Config {
+    /// The number of worker threads.
+    pub num_threads: usize,
+
+    /// The name given to worker threads.
+    pub thread_name: String,
+
+    /// The stack size of worker threads, or the platform default if `None`.
+    pub stack_size: Option<usize>,
+
+    /// Invoked on every worker thread right after it starts.
+    pub on_thread_start: Option<Hook>,
+
+    /// Invoked on every worker thread right before it stops.
+    pub on_thread_stop: Option<Hook>,
+
+    /// The maximum number of threads in the blocking pool.
+    pub max_blocking_threads: usize,
+
+    /// How long an idle thread in the blocking pool waits for a new task before stopping.
+    pub blocking_keep_alive: Duration,
+
+    /// The name given to threads in the blocking pool.
+    pub blocking_thread_name: String,
+
+    /// The maximum number of queued blocking tasks before `try_spawn_blocking` fails.
+    pub blocking_queue_capacity: Option<usize>,
+
+    /// The number of shards in the networking driver.
+    pub reactor_shards: usize,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            num_threads: num_cpus::get().max(1),
+            thread_name: "async-std/executor".to_string(),
+            stack_size: None,
+            on_thread_start: None,
+            on_thread_stop: None,
+            max_blocking_threads: 512,
+            blocking_keep_alive: Duration::from_secs(1),
+            blocking_thread_name: "async-std/blocking".to_string(),
+            blocking_queue_capacity: None,
+            reactor_shards: 1,
+        }
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("num_threads", &self.num_threads)
+            .field("thread_name", &self.thread_name)
+            .field("stack_size", &self.stack_size)
+            .field("max_blocking_threads", &self.max_blocking_threads)
+            .field("blocking_keep_alive", &self.blocking_keep_alive)
+            .field("blocking_thread_name", &self.blocking_thread_name)
+            .field("blocking_queue_capacity", &self.blocking_queue_capacity)
+            .field("reactor_shards", &self.reactor_shards)
+            .finish()
+    }
+}
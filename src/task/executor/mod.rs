@@ -2,12 +2,19 @@
 //!
 //! API bindings between `crate::task` and this module are very simple:
 //!
-//! * The only export is the `schedule` function.
-//! * The only import is the `crate::task::Runnable` type.
+//! * The exports are the `pool`, `config`, `current_config`, `block_in_place`, and `shutdown`
+//!   functions, and the `Pool` and `Config` types.
+//! * The imports are the `crate::task::Runnable`, `crate::task::TaskId`,
+//!   `crate::task::Priority`, and `crate::task::Metrics` types.
 
-pub(crate) use pool::schedule;
+pub(crate) use config::Config;
+pub(crate) use pool::{config, current_config, pool, Pool};
+
+#[cfg(feature = "unstable")]
+pub(crate) use pool::{block_in_place, configure, shutdown};
 
 use sleepers::Sleepers;
 
+mod config;
 mod pool;
 mod sleepers;
@@ -1,64 +1,400 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io;
 use std::iter;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::Waker;
 use std::thread;
 use std::time::Duration;
+#[cfg(feature = "unstable")]
+use std::time::Instant;
 
-use crossbeam_deque::{Injector, Stealer, Worker};
-use once_cell::sync::Lazy;
-use once_cell::unsync::OnceCell;
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+use once_cell::sync;
 
-use crate::task::executor::Sleepers;
-use crate::task::Runnable;
+use crate::task::executor::{Config, Sleepers};
+#[cfg(feature = "unstable")]
+use crate::task::Metrics;
+use crate::task::{Priority, Runnable, TaskId};
 use crate::utils::{abort_on_panic, random};
 
 /// The state of an executor.
-struct Pool {
-    /// The global queue of tasks.
-    injector: Injector<Runnable>,
+pub(crate) struct Pool {
+    /// The global queues of tasks, one per priority.
+    injectors: [Injector<Runnable>; 3],
 
-    /// Handles to local queues for stealing work from worker threads.
-    stealers: Vec<Stealer<Runnable>>,
+    /// Handles to local queues for stealing work from worker threads, one per priority.
+    stealers: Vec<[Stealer<Runnable>; 3]>,
 
     /// Used for putting idle workers to sleep and notifying them when new tasks come in.
     sleepers: Sleepers,
+
+    /// The configuration the worker threads were started with.
+    config: Config,
+
+    /// The number of tasks spawned onto this executor.
+    spawned: AtomicU64,
+
+    /// The number of tasks that have completed or have been cancelled.
+    completed: AtomicU64,
+
+    /// The number of tasks stolen from the local queues of other worker threads.
+    stolen: AtomicU64,
+
+    /// The number of tasks that haven't completed yet.
+    live: AtomicUsize,
+
+    /// Wakers of the tasks that haven't completed yet, used for cancelling idle tasks on shutdown.
+    ///
+    /// The wakers are split into shards by task ID, so that tasks spawned and completed on
+    /// different threads don't contend on the same lock.
+    tasks: Vec<Mutex<HashMap<TaskId, Waker>>>,
+
+    /// Held while checking whether the tasks have completed during shutdown.
+    draining: Mutex<()>,
+
+    /// Notified when the last task completes during shutdown.
+    drained: Condvar,
+
+    /// Set to `true` once the executor starts shutting down.
+    shutting_down: AtomicBool,
+
+    /// Handles of the worker threads, joined on shutdown.
+    threads: Mutex<Vec<thread::JoinHandle<()>>>,
 }
 
-/// Global executor that runs spawned tasks.
-static POOL: Lazy<Pool> = Lazy::new(|| {
-    let num_threads = num_cpus::get().max(1);
-    let mut stealers = Vec::new();
-
-    // Spawn worker threads.
-    for _ in 0..num_threads {
-        let worker = Worker::new_fifo();
-        stealers.push(worker.stealer());
-
-        let proc = Processor {
-            worker,
-            slot: Cell::new(None),
-            slot_runs: Cell::new(0),
-        };
-
-        thread::Builder::new()
-            .name("async-std/executor".to_string())
-            .spawn(|| {
-                let _ = PROCESSOR.with(|p| p.set(proc));
-                abort_on_panic(main_loop);
+impl Pool {
+    /// Creates a new executor and starts its worker threads.
+    pub fn new(config: Config) -> io::Result<Arc<Pool>> {
+        let workers: Vec<_> = (0..config.num_threads)
+            .map(|_| [Worker::new_fifo(), Worker::new_fifo(), Worker::new_fifo()])
+            .collect();
+
+        let pool = Arc::new(Pool {
+            injectors: [Injector::new(), Injector::new(), Injector::new()],
+            stealers: workers
+                .iter()
+                .map(|w| [w[0].stealer(), w[1].stealer(), w[2].stealer()])
+                .collect(),
+            sleepers: Sleepers::new(),
+            config,
+            spawned: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            stolen: AtomicU64::new(0),
+            live: AtomicUsize::new(0),
+            tasks: (0..TASK_SHARDS)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            draining: Mutex::new(()),
+            drained: Condvar::new(),
+            shutting_down: AtomicBool::new(false),
+            threads: Mutex::new(Vec::new()),
+        });
+
+        // Spawn worker threads.
+        for workers in workers {
+            let proc = Processor {
+                pool: pool.clone(),
+                workers,
+                slot: Cell::new(None),
+                slot_runs: Cell::new(0),
+            };
+
+            if let Err((err, _)) = pool.spawn_worker(proc) {
+                // Stop the worker threads that have already been started.
+                pool.close();
+                return Err(err);
+            }
+        }
+
+        Ok(pool)
+    }
+
+    /// Returns the configuration of this executor.
+    #[cfg(feature = "unstable")]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns a snapshot of the executor's queues and counters.
+    #[cfg(feature = "unstable")]
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            num_workers: self.stealers.len(),
+            sleeping_workers: self.sleepers.sleeping(),
+            global_queue_depth: self.injectors.iter().map(|i| i.len()).sum(),
+            local_queue_depths: self
+                .stealers
+                .iter()
+                .map(|s| s.iter().map(|s| s.len()).sum())
+                .collect(),
+            spawned_tasks: self.spawned.load(Ordering::Relaxed),
+            completed_tasks: self.completed.load(Ordering::Relaxed),
+            stolen_tasks: self.stolen.load(Ordering::Relaxed),
+            ..Metrics::default()
+        }
+    }
+
+    /// Records that a task has been spawned onto this executor.
+    ///
+    /// The waker is used for cancelling the task if it is still idle when the executor shuts down.
+    pub fn task_spawned(&self, id: TaskId, waker: Waker) {
+        self.spawned.fetch_add(1, Ordering::Relaxed);
+        self.live.fetch_add(1, Ordering::SeqCst);
+        self.tasks_shard(id).lock().unwrap().insert(id, waker);
+
+        // If the executor has been closed in the meantime, `close` may have missed the waker.
+        // The task gets cancelled when it is scheduled, so just forget it.
+        if self.is_closed() {
+            self.tasks_shard(id).lock().unwrap().remove(&id);
+        }
+    }
+
+    /// Records that a task spawned onto this executor has completed or has been cancelled.
+    pub fn task_completed(&self, id: TaskId) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        self.tasks_shard(id).lock().unwrap().remove(&id);
+
+        // Wake up `shutdown` if it is waiting for the last task. Taking the lock makes sure the
+        // notification doesn't slip in between `shutdown` checking the count and waiting.
+        if self.live.fetch_sub(1, Ordering::SeqCst) == 1
+            && self.shutting_down.load(Ordering::SeqCst)
+        {
+            let _draining = self.draining.lock().unwrap();
+            self.drained.notify_all();
+        }
+    }
+
+    /// Returns the shard of the task wakers the given task belongs to.
+    fn tasks_shard(&self, id: TaskId) -> &Mutex<HashMap<TaskId, Waker>> {
+        &self.tasks[id.0 as usize % self.tasks.len()]
+    }
+
+    /// Schedules a new runnable task for execution.
+    pub fn schedule(&self, task: Runnable, priority: Priority) {
+        PROCESSOR.with(|proc| {
+            // If the current thread is a worker thread of this executor, store it into its task
+            // slot or push it into its local task queue. Otherwise, push it into the global task
+            // queue.
+            match &*proc.borrow() {
+                Some(proc) if ptr::eq(&*proc.pool, self) => {
+                    if priority != Priority::Normal {
+                        // Only tasks with the normal priority go through the slot.
+                        proc.workers[priority.index()].push(task);
+                        self.sleepers.notify_one();
+                    } else if let Some(task) = proc.slot.replace(Some(task)) {
+                        // If the slot already contained a task, push it into the local task queue.
+                        proc.workers[priority.index()].push(task);
+                        self.sleepers.notify_one();
+                    }
+                }
+                _ => {
+                    // Dropping the task cancels it if the executor has been closed.
+                    if !self.is_closed() {
+                        self.injectors[priority.index()].push(task);
+                        self.sleepers.notify_one();
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns `true` if the executor has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.sleepers.is_closed()
+    }
+
+    /// Returns `true` if a task may be spawned onto the executor from the current thread.
+    ///
+    /// Once the executor starts shutting down, only tasks running on it may spawn new tasks, so
+    /// that work split into several tasks can still complete while it waits for them.
+    pub fn accepts_spawns(&self) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        !self.shutting_down.load(Ordering::SeqCst)
+            || PROCESSOR.with(|proc| match &*proc.borrow() {
+                Some(proc) => ptr::eq(&*proc.pool, self),
+                None => false,
             })
-            .expect("cannot start a thread driving tasks");
     }
 
-    Pool {
-        injector: Injector::new(),
-        stealers,
-        sleepers: Sleepers::new(),
+    /// Stops the worker threads.
+    ///
+    /// Tasks that haven't completed yet are cancelled.
+    pub fn close(&self) {
+        self.sleepers.close();
+
+        // Cancel tasks in the global queues.
+        for injector in &self.injectors {
+            while !injector.is_empty() {
+                drop(injector.steal());
+            }
+        }
+
+        // Take the wakers out so that the locks aren't held while cancelled tasks complete. This
+        // also drops the references the wakers hold to the executor.
+        let wakers: Vec<Waker> = self
+            .tasks
+            .iter()
+            .flat_map(|shard| shard.lock().unwrap().drain().collect::<Vec<_>>())
+            .map(|(_, w)| w)
+            .collect();
+
+        // Cancel idle tasks by waking them up. The executor is closed, so scheduling drops them.
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Stops accepting new tasks and waits until the deadline for the remaining ones to complete.
+    ///
+    /// Tasks running on the executor may still spawn new tasks while it waits for them, but
+    /// spawning from anywhere else fails. Tasks that are still alive at the deadline are
+    /// cancelled, and the worker threads are joined.
+    #[cfg(feature = "unstable")]
+    pub fn shutdown(&self, deadline: Instant) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        // Wait for the tasks to complete.
+        let mut draining = self.draining.lock().unwrap();
+        while self.live.load(Ordering::SeqCst) > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            draining = self
+                .drained
+                .wait_timeout(draining, deadline - now)
+                .unwrap()
+                .0;
+        }
+        drop(draining);
+
+        // Stop the worker threads, which cancels the remaining tasks, and wait for them to
+        // exit. A worker thread can't join itself, so skip the current thread.
+        self.close();
+        let current = thread::current().id();
+        loop {
+            let handle = match self.threads.lock().unwrap().pop() {
+                Some(handle) => handle,
+                None => break,
+            };
+            if handle.thread().id() != current {
+                let _ = handle.join();
+            }
+        }
+    }
+
+    /// Starts a worker thread driving the given processor.
+    ///
+    /// If the thread cannot be started, the processor is returned along with the error.
+    #[allow(clippy::result_large_err)]
+    fn spawn_worker(&self, proc: Processor) -> Result<(), (io::Error, Processor)> {
+        let mut builder = thread::Builder::new().name(self.config.thread_name.clone());
+        if let Some(size) = self.config.stack_size {
+            builder = builder.stack_size(size);
+        }
+
+        let on_start = self.config.on_thread_start.clone();
+        let on_stop = self.config.on_thread_stop.clone();
+        let pool = proc.pool.clone();
+
+        // Hand the processor over through a shared slot so that it can be recovered if spawning
+        // the thread fails.
+        let handoff = Arc::new(Mutex::new(Some(proc)));
+        let h = handoff.clone();
+
+        // Hold the lock until the handle is stored so that the thread can't remove it before.
+        let mut threads = self.threads.lock().unwrap();
+        let res = builder.spawn(move || {
+            let proc = h.lock().unwrap().take();
+            PROCESSOR.with(|p| *p.borrow_mut() = proc);
+
+            // Run the stop hook on exit.
+            defer! {
+                if let Some(f) = &on_stop {
+                    f();
+                }
+            }
+
+            if let Some(f) = &on_start {
+                f();
+            }
+            abort_on_panic(main_loop);
+
+            // A thread that has handed off its processor in `block_in_place` is no longer a
+            // worker thread, so it doesn't need to be joined on shutdown.
+            if !pool.is_closed() {
+                let id = thread::current().id();
+                pool.threads
+                    .lock()
+                    .unwrap()
+                    .retain(|h| h.thread().id() != id);
+            }
+        });
+
+        match res {
+            Ok(handle) => {
+                threads.push(handle);
+                Ok(())
+            }
+            Err(err) => Err((err, handoff.lock().unwrap().take().unwrap())),
+        }
     }
-});
+}
+
+/// The number of shards the wakers of live tasks are split into.
+const TASK_SHARDS: usize = 64;
+
+/// Configuration of the global executor.
+///
+/// It is initialized either explicitly through `configure` or with the default configuration when
+/// the executor starts.
+static CONFIG: sync::OnceCell<Config> = sync::OnceCell::new();
+
+/// Global executor that runs spawned tasks.
+///
+/// It is started when the first task is spawned.
+static POOL: sync::OnceCell<Arc<Pool>> = sync::OnceCell::new();
+
+/// Sets the configuration of the global executor.
+///
+/// Returns an error if the executor has already been configured or started.
+#[cfg(feature = "unstable")]
+pub(crate) fn configure(config: Config) -> io::Result<()> {
+    CONFIG.set(config).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the global runtime has already been initialized",
+        )
+    })
+}
+
+/// Returns the configuration of the global executor.
+///
+/// If the global runtime hasn't been configured yet, the default configuration is locked in.
+pub(crate) fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Returns the configuration of the global executor without locking it in.
+///
+/// Until the global runtime is configured, this returns the default configuration.
+pub(crate) fn current_config() -> &'static Config {
+    static DEFAULT: sync::Lazy<Config> = sync::Lazy::new(Config::default);
+    CONFIG.get().unwrap_or(&DEFAULT)
+}
 
 /// The state of a worker thread.
 struct Processor {
-    /// The local task queue.
-    worker: Worker<Runnable>,
+    /// The executor this worker thread belongs to.
+    pool: Arc<Pool>,
+
+    /// The local task queues, one per priority.
+    workers: [Worker<Runnable>; 3],
 
     /// Contains the next task to run as an optimization that skips queues.
     slot: Cell<Option<Runnable>>,
@@ -69,29 +405,23 @@ struct Processor {
 
 thread_local! {
     /// Worker thread state.
-    static PROCESSOR: OnceCell<Processor> = OnceCell::new();
+    ///
+    /// This is `None` on threads that are not worker threads, or that have handed off their
+    /// processor to another thread in `block_in_place`.
+    static PROCESSOR: RefCell<Option<Processor>> = RefCell::new(None);
 }
 
-/// Schedules a new runnable task for execution.
-pub(crate) fn schedule(task: Runnable) {
-    PROCESSOR.with(|proc| {
-        // If the current thread is a worker thread, store it into its task slot or push it into
-        // its local task queue. Otherwise, push it into the global task queue.
-        match proc.get() {
-            Some(proc) => {
-                // Replace the task in the slot.
-                if let Some(task) = proc.slot.replace(Some(task)) {
-                    // If the slot already contained a task, push it into the local task queue.
-                    proc.worker.push(task);
-                    POOL.sleepers.notify_one();
-                }
-            }
-            None => {
-                POOL.injector.push(task);
-                POOL.sleepers.notify_one();
-            }
-        }
-    })
+/// Returns the global executor, starting it if needed.
+pub(crate) fn pool() -> &'static Pool {
+    POOL.get_or_init(|| Pool::new(config().clone()).expect("cannot start a thread driving tasks"))
+}
+
+/// Shuts down the global executor, if it has been started.
+#[cfg(feature = "unstable")]
+pub(crate) fn shutdown(deadline: Instant) {
+    if let Some(pool) = POOL.get() {
+        pool.shutdown(deadline);
+    }
 }
 
 /// Main loop running a worker thread.
@@ -101,10 +431,13 @@ fn main_loop() {
     /// Number of short sleeps when no runnable task in found.
     const SLEEPS: u32 = 1;
 
+    let pool = PROCESSOR.with(|proc| proc.borrow().as_ref().unwrap().pool.clone());
+
     // The number of times the thread didn't find work in a row.
     let mut fails = 0;
 
-    loop {
+    // Stop when the executor is closed or when the processor has been handed off.
+    while !pool.is_closed() && PROCESSOR.with(|proc| proc.borrow().is_some()) {
         // Try to find a runnable task.
         match find_runnable() {
             Some(task) => {
@@ -122,12 +455,45 @@ fn main_loop() {
                 } else if fails <= YIELDS + SLEEPS {
                     thread::sleep(Duration::from_micros(10));
                 } else {
-                    POOL.sleepers.wait();
+                    pool.sleepers.wait();
                     fails = 0;
                 }
             }
         }
     }
+
+    // Cancel tasks left in the local queue.
+    if let Some(proc) = PROCESSOR.with(|proc| proc.borrow_mut().take()) {
+        drop(proc.slot.take());
+        for worker in &proc.workers {
+            while let Some(task) = worker.pop() {
+                drop(task);
+            }
+        }
+    }
+}
+
+/// Runs a blocking function on the current thread, handing off its processor to a new worker
+/// thread if the current thread is a worker thread.
+#[cfg(feature = "unstable")]
+pub(crate) fn block_in_place<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let proc = match PROCESSOR.with(|proc| proc.borrow_mut().take()) {
+        Some(proc) => proc,
+        None => return f(),
+    };
+
+    // Let a new thread take over the local queue so that its tasks keep running while this
+    // thread is blocked. After the current task yields, this thread stops being a worker thread.
+    let pool = proc.pool.clone();
+    if let Err((_, proc)) = pool.spawn_worker(proc) {
+        // If no thread can be started, block the worker thread anyway.
+        PROCESSOR.with(|p| *p.borrow_mut() = Some(proc));
+    }
+
+    f()
 }
 
 /// Find the next runnable task.
@@ -136,44 +502,65 @@ fn find_runnable() -> Option<Runnable> {
     const SLOT_LIMIT: u32 = 16;
 
     PROCESSOR.with(|proc| {
-        let proc = proc.get().unwrap();
-
-        // Try taking a task from the slot.
-        let runs = proc.slot_runs.get();
-        if runs < SLOT_LIMIT {
-            if let Some(task) = proc.slot.take() {
-                proc.slot_runs.set(runs + 1);
-                return Some(task);
+        let proc = proc.borrow();
+        let proc = proc.as_ref()?;
+
+        // Look for tasks in order of priority.
+        Priority::ALL.iter().find_map(|&priority| {
+            if priority == Priority::Normal {
+                // Try taking a task from the slot.
+                let runs = proc.slot_runs.get();
+                if runs < SLOT_LIMIT {
+                    if let Some(task) = proc.slot.take() {
+                        proc.slot_runs.set(runs + 1);
+                        return Some(task);
+                    }
+                }
+                proc.slot_runs.set(0);
             }
-        }
-        proc.slot_runs.set(0);
-
-        // Pop a task from the local queue, if not empty.
-        proc.worker.pop().or_else(|| {
-            // Otherwise, we need to look for a task elsewhere.
-            iter::repeat_with(|| {
-                // Try stealing a batch of tasks from the global queue.
-                POOL.injector
-                    .steal_batch_and_pop(&proc.worker)
-                    // Or try stealing a batch of tasks from one of the other threads.
-                    .or_else(|| {
-                        // First, pick a random starting point in the list of local queues.
-                        let len = POOL.stealers.len();
-                        let start = random(len as u32) as usize;
-
-                        // Try stealing a batch of tasks from each local queue starting from the
-                        // chosen point.
-                        let (l, r) = POOL.stealers.split_at(start);
-                        let stealers = r.iter().chain(l.iter());
-                        stealers
-                            .map(|s| s.steal_batch_and_pop(&proc.worker))
-                            .collect()
-                    })
-            })
-            // Loop while no task was stolen and any steal operation needs to be retried.
-            .find(|s| !s.is_retry())
-            // Extract the stolen task, if there is one.
-            .and_then(|s| s.success())
+
+            find_queued(proc, priority.index())
+        })
+    })
+}
+
+/// Find a task in the queues of the given priority index.
+fn find_queued(proc: &Processor, index: usize) -> Option<Runnable> {
+    let pool = &proc.pool;
+    let worker = &proc.workers[index];
+
+    // Pop a task from the local queue, if not empty.
+    worker.pop().or_else(|| {
+        // Otherwise, we need to look for a task elsewhere.
+        iter::repeat_with(|| {
+            // Try stealing a batch of tasks from the global queue.
+            pool.injectors[index]
+                .steal_batch_and_pop(worker)
+                // Or try stealing a batch of tasks from one of the other threads.
+                .or_else(|| {
+                    // First, pick a random starting point in the list of local queues.
+                    let len = pool.stealers.len();
+                    let start = random(len as u32) as usize;
+
+                    // Try stealing a batch of tasks from each local queue starting from the
+                    // chosen point.
+                    let (l, r) = pool.stealers.split_at(start);
+                    let stealers = r.iter().chain(l.iter());
+                    let steal: Steal<Runnable> = stealers
+                        .map(|s| s[index].steal_batch_and_pop(worker))
+                        .collect();
+
+                    // The local queue was empty, so everything in it now has been stolen.
+                    if steal.is_success() {
+                        let stolen = worker.len() as u64 + 1;
+                        pool.stolen.fetch_add(stolen, Ordering::Relaxed);
+                    }
+                    steal
+                })
         })
+        // Loop while no task was stolen and any steal operation needs to be retried.
+        .find(|s| !s.is_retry())
+        // Extract the stolen task, if there is one.
+        .and_then(|s| s.success())
     })
 }
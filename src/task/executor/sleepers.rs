@@ -1,28 +1,49 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Condvar, Mutex};
 
+use crate::net::driver::{self, Parker};
+
 /// The place where worker threads go to sleep.
 ///
 /// Similar to how thread parking works, if a notification comes up while no threads are sleeping,
 /// the next thread that attempts to go to sleep will pick up the notification immediately.
+///
+/// A sleeping thread polls its shard of the networking driver if nobody else does, so that tasks
+/// woken up by I/O events are scheduled on the thread that is going to run them.
 pub struct Sleepers {
-    /// How many threads are currently a sleep.
-    sleep: Mutex<usize>,
+    /// The threads that are currently asleep.
+    sleep: Mutex<State>,
 
     /// A condvar for notifying sleeping threads.
     wake: Condvar,
 
     /// Set to `true` if a notification came up while nobody was sleeping.
     notified: AtomicBool,
+
+    /// Set to `true` once the executor is closed and threads should no longer go to sleep.
+    closed: AtomicBool,
+}
+
+/// The threads that are currently asleep.
+struct State {
+    /// How many threads are waiting on the condvar.
+    count: usize,
+
+    /// The shards of the networking driver polled by sleeping threads.
+    parked: Vec<usize>,
 }
 
 impl Sleepers {
     /// Creates a new `Sleepers`.
     pub fn new() -> Sleepers {
         Sleepers {
-            sleep: Mutex::new(0),
+            sleep: Mutex::new(State {
+                count: 0,
+                parked: Vec::new(),
+            }),
             wake: Condvar::new(),
             notified: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
         }
     }
 
@@ -30,9 +51,33 @@ impl Sleepers {
     pub fn wait(&self) {
         let mut sleep = self.sleep.lock().unwrap();
 
+        if self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
         if !self.notified.swap(false, Ordering::SeqCst) {
-            *sleep += 1;
-            let _ = self.wake.wait(sleep).unwrap();
+            match Parker::new() {
+                Some(parker) => {
+                    // Poll for I/O events until woken up.
+                    let shard = parker.shard();
+                    sleep.parked.push(shard);
+                    drop(sleep);
+                    parker.park();
+
+                    // Stop polling only after leaving the list, so that another thread parked on
+                    // the same shard doesn't get removed instead.
+                    let mut sleep = self.sleep.lock().unwrap();
+                    if let Some(i) = sleep.parked.iter().position(|&s| s == shard) {
+                        sleep.parked.swap_remove(i);
+                    }
+                    drop(sleep);
+                    drop(parker);
+                }
+                None => {
+                    sleep.count += 1;
+                    drop(self.wake.wait(sleep).unwrap());
+                }
+            }
         }
     }
 
@@ -41,12 +86,37 @@ impl Sleepers {
         if !self.notified.load(Ordering::SeqCst) {
             let mut sleep = self.sleep.lock().unwrap();
 
-            if *sleep > 0 {
-                *sleep -= 1;
+            if sleep.count > 0 {
+                sleep.count -= 1;
                 self.wake.notify_one();
+            } else if let Some(shard) = sleep.parked.pop() {
+                driver::unpark(shard);
             } else {
                 self.notified.store(true, Ordering::SeqCst);
             }
         }
     }
+
+    /// Wakes up all sleeping threads and prevents them from going to sleep again.
+    pub fn close(&self) {
+        let mut sleep = self.sleep.lock().unwrap();
+        self.closed.store(true, Ordering::SeqCst);
+        sleep.count = 0;
+        self.wake.notify_all();
+        for shard in sleep.parked.drain(..) {
+            driver::unpark(shard);
+        }
+    }
+
+    /// Returns the number of sleeping threads.
+    #[cfg(feature = "unstable")]
+    pub fn sleeping(&self) -> usize {
+        let sleep = self.sleep.lock().unwrap();
+        sleep.count + sleep.parked.len()
+    }
+
+    /// Returns `true` if `close` has been called.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
 }
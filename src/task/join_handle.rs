@@ -1,25 +1,39 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
 use std::future::Future;
+use std::panic;
 use std::pin::Pin;
 
+#[cfg(feature = "unstable")]
+use crate::future;
+use crate::task::panic::Panic;
 use crate::task::{Context, Poll, Task};
 
 /// A handle that awaits the result of a task.
 ///
 /// Dropping a [`JoinHandle`] will detach the task, meaning that there is no longer
-/// a handle to the task and no way to `join` on it.
+/// a handle to the task and no way to `join` on it. To stop the task instead, use [`cancel`] or
+/// [`abort_on_drop`].
+///
+/// Awaiting the handle of a task that has panicked resumes the panic in the awaiting task. To
+/// handle the panic instead, use [`try_join`].
 ///
 /// Created when a task is [spawned].
 ///
 /// [spawned]: fn.spawn.html
+/// [`try_join`]: #method.try_join
+/// [`cancel`]: #method.cancel
+/// [`abort_on_drop`]: #method.abort_on_drop
 #[derive(Debug)]
-pub struct JoinHandle<T>(async_task::JoinHandle<T, Task>);
+pub struct JoinHandle<T>(async_task::JoinHandle<Result<T, Panic>, Task>);
 
-unsafe impl<T> Send for JoinHandle<T> {}
+unsafe impl<T: Send> Send for JoinHandle<T> {}
 unsafe impl<T> Sync for JoinHandle<T> {}
 
 impl<T> JoinHandle<T> {
     /// Creates a new `JoinHandle`.
-    pub(crate) fn new(inner: async_task::JoinHandle<T, Task>) -> JoinHandle<T> {
+    pub(crate) fn new(inner: async_task::JoinHandle<Result<T, Panic>, Task>) -> JoinHandle<T> {
         JoinHandle(inner)
     }
 
@@ -41,6 +55,114 @@ impl<T> JoinHandle<T> {
     pub fn task(&self) -> &Task {
         self.0.tag()
     }
+
+    /// Awaits the result of the task, returning an error if it has panicked.
+    ///
+    /// Unlike awaiting the [`JoinHandle`] directly, this doesn't propagate the panic into the
+    /// current task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::task;
+    ///
+    /// let handle = task::spawn(async {
+    ///     panic!("boom");
+    /// });
+    ///
+    /// let err = handle.try_join().await.unwrap_err();
+    /// assert!(err.is_panic());
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub async fn try_join(mut self) -> Result<T, JoinError> {
+        future::poll_fn(|cx| self.poll_join(cx)).await
+    }
+
+    /// Cancels the task.
+    ///
+    /// The task's future will not be polled again. If the task is currently running, it is
+    /// stopped at its next `.await` point, after which its future is dropped by the executor.
+    ///
+    /// Returns the output of the task if it has completed before being cancelled.
+    ///
+    /// If the task has panicked before being cancelled, the panic is resumed in the current task,
+    /// just like when awaiting the handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::future;
+    /// use async_std::task;
+    ///
+    /// let handle = task::spawn(future::pending::<()>());
+    /// assert_eq!(handle.cancel().await, None);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub async fn cancel(mut self) -> Option<T> {
+        self.0.cancel();
+        match (&mut self.0).await {
+            Some(Ok(val)) => Some(val),
+            Some(Err(panic)) => panic.into_error().resume(),
+            None => None,
+        }
+    }
+
+    /// Converts the handle into one that cancels the task when dropped.
+    ///
+    /// This is useful for making sure a task doesn't outlive the code that spawned it, even on
+    /// early returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::task;
+    ///
+    /// let handle = task::spawn(async {
+    ///     task::sleep(Duration::from_secs(10)).await;
+    /// })
+    /// .abort_on_drop();
+    ///
+    /// // The task is cancelled here.
+    /// drop(handle);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn abort_on_drop(self) -> AbortOnDrop<T> {
+        AbortOnDrop(Some(self))
+    }
+
+    /// Cancels the task without waiting for it.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn abort(&self) {
+        self.0.cancel();
+    }
+
+    /// Polls the result of the task, returning an error if it has panicked or been cancelled.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn poll_join(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, JoinError>> {
+        match Pin::new(&mut self.0).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Some(Ok(val))) => Poll::Ready(Ok(val)),
+            Poll::Ready(Some(Err(panic))) => Poll::Ready(Err(panic.into_error())),
+            Poll::Ready(None) => Poll::Ready(Err(JoinError::cancelled())),
+        }
+    }
 }
 
 impl<T> Future for JoinHandle<T> {
@@ -49,8 +171,172 @@ impl<T> Future for JoinHandle<T> {
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match Pin::new(&mut self.0).poll(cx) {
             Poll::Pending => Poll::Pending,
-            Poll::Ready(None) => panic!("cannot await the result of a panicked task"),
-            Poll::Ready(Some(val)) => Poll::Ready(val),
+            Poll::Ready(None) => panic!("cannot await the result of a cancelled task"),
+            Poll::Ready(Some(Ok(val))) => Poll::Ready(val),
+            Poll::Ready(Some(Err(panic))) => panic.into_error().resume(),
+        }
+    }
+}
+
+/// A handle that cancels its task when dropped.
+///
+/// Apart from that, it works just like a [`JoinHandle`] and can be awaited to get the result of
+/// the task.
+///
+/// Created by [`JoinHandle::abort_on_drop`].
+///
+/// [`JoinHandle`]: struct.JoinHandle.html
+/// [`JoinHandle::abort_on_drop`]: struct.JoinHandle.html#method.abort_on_drop
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+#[derive(Debug)]
+pub struct AbortOnDrop<T>(Option<JoinHandle<T>>);
+
+#[cfg(feature = "unstable")]
+impl<T> AbortOnDrop<T> {
+    /// Returns a handle to the underlying task.
+    pub fn task(&self) -> &Task {
+        self.0.as_ref().unwrap().task()
+    }
+
+    /// Cancels the task.
+    ///
+    /// See [`JoinHandle::cancel`] for details.
+    ///
+    /// [`JoinHandle::cancel`]: struct.JoinHandle.html#method.cancel
+    pub async fn cancel(mut self) -> Option<T> {
+        self.0.take().unwrap().cancel().await
+    }
+
+    /// Converts back into a [`JoinHandle`] that detaches the task when dropped.
+    ///
+    /// [`JoinHandle`]: struct.JoinHandle.html
+    pub fn detach(mut self) -> JoinHandle<T> {
+        self.0.take().unwrap()
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl<T> Future for AbortOnDrop<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(self.0.as_mut().unwrap()).poll(cx)
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.0 {
+            handle.abort();
         }
     }
 }
+
+/// An error returned when a task did not run to completion.
+///
+/// Returned by [`JoinHandle::try_join`].
+///
+/// [`JoinHandle::try_join`]: struct.JoinHandle.html#method.try_join
+pub struct JoinError {
+    repr: Repr,
+}
+
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+enum Repr {
+    /// The task has panicked with the given payload.
+    Panic(Box<dyn Any + Send + 'static>),
+
+    /// The task has been cancelled, for example because its executor has been dropped.
+    Cancelled,
+}
+
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+impl JoinError {
+    /// Creates an error for a task that has panicked.
+    pub(crate) fn panic(payload: Box<dyn Any + Send + 'static>) -> JoinError {
+        JoinError {
+            repr: Repr::Panic(payload),
+        }
+    }
+
+    /// Creates an error for a task that has been cancelled.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn cancelled() -> JoinError {
+        JoinError {
+            repr: Repr::Cancelled,
+        }
+    }
+
+    /// Returns `true` if the task has panicked.
+    pub fn is_panic(&self) -> bool {
+        match self.repr {
+            Repr::Panic(_) => true,
+            Repr::Cancelled => false,
+        }
+    }
+
+    /// Returns `true` if the task has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        match self.repr {
+            Repr::Panic(_) => false,
+            Repr::Cancelled => true,
+        }
+    }
+
+    /// Consumes the error and returns the panic payload, if the task has panicked.
+    ///
+    /// The payload can be passed to [`std::panic::resume_unwind`] to propagate the panic.
+    ///
+    /// [`std::panic::resume_unwind`]: https://doc.rust-lang.org/std/panic/fn.resume_unwind.html
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send + 'static>, JoinError> {
+        match self.repr {
+            Repr::Panic(payload) => Ok(payload),
+            Repr::Cancelled => Err(self),
+        }
+    }
+
+    /// Resumes unwinding with the panic payload.
+    fn resume(self) -> ! {
+        match self.repr {
+            Repr::Panic(payload) => panic::resume_unwind(payload),
+            Repr::Cancelled => panic!("cannot await the result of a cancelled task"),
+        }
+    }
+
+    /// Returns the panic message, if the task has panicked with a string payload.
+    pub(crate) fn panic_message(&self) -> Option<&str> {
+        match &self.repr {
+            Repr::Panic(payload) => payload
+                .downcast_ref::<&'static str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(|s| s.as_str())),
+            Repr::Cancelled => None,
+        }
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.repr {
+            Repr::Panic(_) => f
+                .debug_tuple("Panic")
+                .field(&self.panic_message().unwrap_or("Box<Any>"))
+                .finish(),
+            Repr::Cancelled => f.debug_tuple("Cancelled").finish(),
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.repr, self.panic_message()) {
+            (Repr::Panic(_), Some(msg)) => write!(f, "task panicked: {}", msg),
+            (Repr::Panic(_), None) => "task panicked".fmt(f),
+            (Repr::Cancelled, _) => "task was cancelled".fmt(f),
+        }
+    }
+}
+
+impl Error for JoinError {}
@@ -0,0 +1,261 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+
+use crossbeam_deque::{Injector, Steal};
+use crossbeam_utils::sync::Unparker;
+
+use crate::task::block_on::block_on_with;
+use crate::task::{Builder, JoinHandle, Runnable, TaskId};
+
+thread_local! {
+    /// The queue of the local executor running on the current thread.
+    static CURRENT: RefCell<Option<Arc<LocalQueue>>> = RefCell::new(None);
+}
+
+/// An executor that runs `!Send` tasks on the current thread.
+///
+/// Tasks are spawned with [`LocalExecutor::spawn`] or, from within [`LocalExecutor::run`], with
+/// [`task::spawn_local`]. They only make progress while the thread is blocked in
+/// [`LocalExecutor::run`].
+///
+/// Dropping the executor cancels the tasks that haven't completed yet.
+///
+/// [`LocalExecutor::spawn`]: #method.spawn
+/// [`LocalExecutor::run`]: #method.run
+/// [`task::spawn_local`]: fn.spawn_local.html
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// use async_std::task::LocalExecutor;
+///
+/// let executor = LocalExecutor::new();
+/// let log = Rc::new(RefCell::new(Vec::new()));
+///
+/// let l = log.clone();
+/// let handle = executor.spawn(async move { l.borrow_mut().push(1) });
+///
+/// executor.run(async {
+///     handle.await;
+///     log.borrow_mut().push(2);
+/// });
+///
+/// assert_eq!(*log.borrow(), vec![1, 2]);
+/// ```
+#[derive(Debug)]
+pub struct LocalExecutor {
+    /// The queue of runnable tasks.
+    queue: Arc<LocalQueue>,
+
+    /// Makes the type `!Send` and `!Sync`.
+    _marker: PhantomData<Rc<()>>,
+}
+
+impl LocalExecutor {
+    /// Creates a new local executor.
+    pub fn new() -> LocalExecutor {
+        LocalExecutor {
+            queue: Arc::new(LocalQueue {
+                tasks: Injector::new(),
+                unparker: Mutex::new(None),
+                wakers: Mutex::new(HashMap::new()),
+                closed: AtomicBool::new(false),
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Spawns a task onto this executor.
+    ///
+    /// The task will start running once the current thread calls [`run`].
+    ///
+    /// [`run`]: #method.run
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + 'static,
+        T: 'static,
+    {
+        Builder::new().spawn_local_on(self.queue.clone(), future)
+    }
+
+    /// Blocks the current thread on a future's result while running spawned tasks.
+    ///
+    /// This is similar to [`task::block_on`], except the thread runs tasks spawned onto this
+    /// executor while waiting for the future to complete.
+    ///
+    /// [`task::block_on`]: fn.block_on.html
+    pub fn run<F, T>(&self, future: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        /// Maximum number of tasks to run before polling the future again.
+        const BATCH: usize = 64;
+
+        // Make this executor the current one while the future is running.
+        let prev = CURRENT.with(|c| c.replace(Some(self.queue.clone())));
+        defer! {
+            CURRENT.with(|c| *c.borrow_mut() = prev);
+        }
+
+        let mut registered = false;
+        let res = block_on_with(future, |unparker| {
+            // Make sure scheduling a task wakes up the current thread.
+            if !registered {
+                *self.queue.unparker.lock().unwrap() = Some(unparker.clone());
+                registered = true;
+            }
+
+            let mut progress = false;
+            for _ in 0..BATCH {
+                match self.queue.pop() {
+                    Some(task) => {
+                        task.run();
+                        progress = true;
+                    }
+                    None => break,
+                }
+            }
+            progress
+        });
+
+        *self.queue.unparker.lock().unwrap() = None;
+        res
+    }
+}
+
+impl Default for LocalExecutor {
+    fn default() -> LocalExecutor {
+        LocalExecutor::new()
+    }
+}
+
+impl Drop for LocalExecutor {
+    fn drop(&mut self) {
+        // Wake up idle tasks so that they end up in the queue. Taking the wakers out also drops
+        // the references they hold to the queue.
+        let wakers: Vec<Waker> = self
+            .queue
+            .wakers
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, w)| w)
+            .collect();
+        for waker in wakers {
+            waker.wake();
+        }
+
+        self.queue.closed.store(true, Ordering::SeqCst);
+
+        // Cancel the tasks in the queue.
+        while let Some(task) = self.queue.pop() {
+            drop(task);
+        }
+    }
+}
+
+/// The queue of tasks in a local executor.
+#[derive(Debug)]
+pub(crate) struct LocalQueue {
+    /// Tasks that are ready to run.
+    tasks: Injector<Runnable>,
+
+    /// Wakes up the thread running the executor.
+    unparker: Mutex<Option<Unparker>>,
+
+    /// Wakers of the tasks that haven't completed yet, used for cancelling idle tasks on drop.
+    wakers: Mutex<HashMap<TaskId, Waker>>,
+
+    /// Set to `true` when the executor is dropped.
+    closed: AtomicBool,
+}
+
+impl LocalQueue {
+    /// Returns the queue of the local executor running on the current thread.
+    pub fn current() -> Option<Arc<LocalQueue>> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    /// Schedules a task for execution.
+    pub fn schedule(&self, task: Runnable) {
+        if self.closed.load(Ordering::SeqCst) {
+            // The task may be woken up by another thread, but its future must only be dropped by
+            // the thread that spawned it. Since nobody is going to run it anymore, leak it.
+            mem::forget(task);
+            return;
+        }
+
+        self.tasks.push(task);
+        if let Some(unparker) = self.unparker.lock().unwrap().as_ref() {
+            unparker.unpark();
+        }
+    }
+
+    /// Records that a task has been spawned onto this executor.
+    pub fn task_spawned(&self, id: TaskId, waker: Waker) {
+        self.wakers.lock().unwrap().insert(id, waker);
+    }
+
+    /// Records that a task spawned onto this executor has completed or has been cancelled.
+    pub fn task_completed(&self, id: TaskId) {
+        self.wakers.lock().unwrap().remove(&id);
+    }
+
+    /// Pops a task from the queue.
+    fn pop(&self) -> Option<Runnable> {
+        loop {
+            match self.tasks.steal() {
+                Steal::Success(task) => return Some(task),
+                Steal::Empty => return None,
+                Steal::Retry => {}
+            }
+        }
+    }
+}
+
+/// Spawns a `!Send` task onto the local executor running on the current thread.
+///
+/// This function is similar to [`task::spawn`], except the future doesn't have to implement
+/// `Send`. The task runs on the current thread, driven by [`LocalExecutor::run`].
+///
+/// [`task::spawn`]: fn.spawn.html
+/// [`LocalExecutor::run`]: struct.LocalExecutor.html#method.run
+///
+/// # Panics
+///
+/// This function will panic if not called within [`LocalExecutor::run`].
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use async_std::task::{self, LocalExecutor};
+///
+/// LocalExecutor::new().run(async {
+///     let rc = Rc::new(1);
+///     let handle = task::spawn_local(async move { *rc + 2 });
+///     assert_eq!(handle.await, 3);
+/// });
+/// ```
+#[track_caller]
+pub fn spawn_local<F, T>(future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + 'static,
+    T: 'static,
+{
+    Builder::new()
+        .spawn_local(future)
+        .expect("cannot spawn task")
+}
@@ -0,0 +1,120 @@
+use crate::task::{executor, spawn_blocking};
+
+/// A snapshot of the runtime's counters and queue depths.
+///
+/// Created by [`task::metrics`] for the global executor, or by [`Executor::metrics`] for a
+/// separate executor.
+///
+/// The snapshot is taken without stopping the worker threads, so its values may be slightly out
+/// of sync with each other.
+///
+/// [`task::metrics`]: fn.metrics.html
+/// [`Executor::metrics`]: struct.Executor.html#method.metrics
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    pub(crate) num_workers: usize,
+    pub(crate) sleeping_workers: usize,
+    pub(crate) global_queue_depth: usize,
+    pub(crate) local_queue_depths: Vec<usize>,
+    pub(crate) spawned_tasks: u64,
+    pub(crate) completed_tasks: u64,
+    pub(crate) stolen_tasks: u64,
+    pub(crate) blocking_threads: usize,
+    pub(crate) idle_blocking_threads: usize,
+    pub(crate) blocking_queue_depth: usize,
+}
+
+impl Metrics {
+    /// Returns the number of worker threads in the executor.
+    pub fn num_workers(&self) -> usize {
+        self.num_workers
+    }
+
+    /// Returns the number of worker threads sleeping while waiting for tasks.
+    pub fn sleeping_workers(&self) -> usize {
+        self.sleeping_workers
+    }
+
+    /// Returns the number of tasks in the global queue.
+    ///
+    /// Tasks are pushed into the global queue when they are woken up outside of the executor's
+    /// worker threads.
+    pub fn global_queue_depth(&self) -> usize {
+        self.global_queue_depth
+    }
+
+    /// Returns the number of tasks in the local queue of each worker thread.
+    pub fn local_queue_depths(&self) -> &[usize] {
+        &self.local_queue_depths
+    }
+
+    /// Returns the number of tasks spawned onto the executor so far.
+    pub fn spawned_tasks(&self) -> u64 {
+        self.spawned_tasks
+    }
+
+    /// Returns the number of tasks that have completed or have been cancelled so far.
+    pub fn completed_tasks(&self) -> u64 {
+        self.completed_tasks
+    }
+
+    /// Returns the number of tasks that are still alive.
+    pub fn live_tasks(&self) -> u64 {
+        self.spawned_tasks.saturating_sub(self.completed_tasks)
+    }
+
+    /// Returns the number of tasks worker threads have stolen from each other so far.
+    ///
+    /// Tasks taken from the global queue are not counted.
+    pub fn stolen_tasks(&self) -> u64 {
+        self.stolen_tasks
+    }
+
+    /// Returns the number of threads in the blocking pool used by [`spawn_blocking`].
+    ///
+    /// The blocking pool is shared by all executors.
+    ///
+    /// [`spawn_blocking`]: fn.spawn_blocking.html
+    pub fn blocking_threads(&self) -> usize {
+        self.blocking_threads
+    }
+
+    /// Returns the number of threads in the blocking pool waiting for tasks.
+    pub fn idle_blocking_threads(&self) -> usize {
+        self.idle_blocking_threads
+    }
+
+    /// Returns the number of blocking tasks waiting for a thread.
+    pub fn blocking_queue_depth(&self) -> usize {
+        self.blocking_queue_depth
+    }
+
+    /// Fills in the counters of the blocking pool.
+    pub(crate) fn with_blocking_pool(mut self) -> Metrics {
+        self.blocking_threads = spawn_blocking::num_threads();
+        self.idle_blocking_threads = spawn_blocking::num_idle_threads();
+        self.blocking_queue_depth = spawn_blocking::queue_depth();
+        self
+    }
+}
+
+/// Returns a snapshot of the global executor's counters and queue depths.
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use async_std::task;
+///
+/// task::spawn(async {}).await;
+///
+/// let metrics = task::metrics();
+/// assert!(metrics.spawned_tasks() >= 1);
+/// println!("{} tasks alive", metrics.live_tasks());
+/// #
+/// # })
+/// ```
+pub fn metrics() -> Metrics {
+    executor::pool().metrics().with_blocking_pool()
+}
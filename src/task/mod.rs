@@ -17,9 +17,10 @@
 //! tasks using the atomically-reference-counted container, [`Arc`].
 //!
 //! Fatal logic errors in Rust cause *thread panic*, during which a thread will unwind the stack,
-//! running destructors and freeing owned resources. If a panic occurs inside a task, there is no
-//! meaningful way of recovering, so the panic will propagate through any thread boundaries all the
-//! way to the root task. This is also known as a "panic = abort" model.
+//! running destructors and freeing owned resources. If a panic occurs inside a spawned task, the
+//! task is stopped and the panic is caught by the executor, leaving other tasks running. Awaiting
+//! the task's [`JoinHandle`] resumes the panic in the awaiting task. If the task is detached,
+//! the panic is reported through the task panic hook instead.
 //!
 //! ## Spawning a task
 //!
@@ -128,6 +129,8 @@ cfg_alloc! {
 cfg_std! {
     pub use yield_now::yield_now;
     mod yield_now;
+
+    pub(crate) mod budget;
 }
 
 cfg_default! {
@@ -137,6 +140,10 @@ cfg_default! {
     pub use task::Task;
     pub use task_id::TaskId;
     pub use join_handle::JoinHandle;
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) use join_handle::JoinError;
+    #[cfg(not(feature = "unstable"))]
+    pub(crate) use priority::Priority;
     pub use sleep::sleep;
     pub use spawn::spawn;
     pub use task_local::{AccessError, LocalKey};
@@ -147,8 +154,11 @@ cfg_default! {
     mod block_on;
     mod builder;
     mod current;
-    mod executor;
+    pub(crate) mod executor;
     mod join_handle;
+    mod panic;
+    mod priority;
+    mod registry;
     mod sleep;
     mod spawn;
     mod spawn_blocking;
@@ -161,3 +171,25 @@ cfg_default! {
     #[cfg(not(any(feature = "unstable", test)))]
     pub(crate) use spawn_blocking::spawn_blocking;
 }
+
+cfg_unstable_default! {
+    pub use block_in_place::block_in_place;
+    pub use join_handle::{AbortOnDrop, JoinError};
+    pub use local_executor::{spawn_local, LocalExecutor};
+    pub use metrics::{metrics, Metrics};
+    pub use panic::{set_panic_hook, take_panic_hook};
+    pub use priority::Priority;
+    pub use registry::{dump, enable_registry, TaskDump, TaskInfo, TaskState};
+    pub use runtime::{Executor, Handle, Runtime, RuntimeBuilder};
+    pub use simulation::Simulation;
+    pub use sleep::{sleep_until, Sleep};
+    pub use spawn_blocking::try_spawn_blocking;
+    pub use task_group::TaskGroup;
+
+    mod block_in_place;
+    mod local_executor;
+    mod metrics;
+    mod runtime;
+    mod simulation;
+    mod task_group;
+}
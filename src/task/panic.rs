@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::RwLock;
+
+use kv_log_macro::error;
+use once_cell::sync::Lazy;
+use pin_project_lite::pin_project;
+
+use crate::task::{Context, JoinError, Poll, Task};
+use crate::utils::abort_on_panic;
+
+/// A function invoked with panics that have not been observed through a `JoinHandle`.
+type Hook = Box<dyn Fn(&Task, &JoinError) + Send + Sync + 'static>;
+
+/// The panic hook, or `None` if the default one is used.
+static HOOK: Lazy<RwLock<Option<Hook>>> = Lazy::new(|| RwLock::new(None));
+
+/// Registers a custom panic hook for tasks, replacing any that was previously registered.
+///
+/// The hook is invoked when a task panics and the panic is not observed through its
+/// [`JoinHandle`], either because the handle has been dropped and the task is detached, or
+/// because the handle is dropped after the task has panicked. It receives a handle to the task
+/// that panicked and the error describing the panic.
+///
+/// The default hook logs the task id, the task name, and the panic message at the error level.
+///
+/// [`JoinHandle`]: struct.JoinHandle.html
+///
+/// # Examples
+///
+/// ```
+/// use async_std::task;
+///
+/// task::set_panic_hook(Box::new(|task, err| {
+///     eprintln!("task {} panicked: {}", task.id(), err);
+/// }));
+/// ```
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+#[allow(clippy::type_complexity)]
+pub fn set_panic_hook(hook: Box<dyn Fn(&Task, &JoinError) + Send + Sync + 'static>) {
+    *HOOK.write().unwrap() = Some(hook);
+}
+
+/// Unregisters the current panic hook for tasks and returns it.
+///
+/// If no custom hook is registered, the default hook is returned.
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+#[allow(clippy::type_complexity)]
+pub fn take_panic_hook() -> Box<dyn Fn(&Task, &JoinError) + Send + Sync + 'static> {
+    HOOK.write()
+        .unwrap()
+        .take()
+        .unwrap_or_else(|| Box::new(default_hook))
+}
+
+/// The panic hook used when no custom hook is registered.
+fn default_hook(task: &Task, err: &JoinError) {
+    error!("task panicked", {
+        task_id: task.id().0,
+        task_name: task.name().unwrap_or("<unnamed>"),
+        message: err.panic_message().unwrap_or("Box<Any>"),
+    });
+}
+
+/// A panic caught while running a task.
+///
+/// If this value is dropped without its payload being taken out, the panic hook is invoked.
+pub(crate) struct Panic {
+    /// The error holding the panic payload.
+    err: Option<JoinError>,
+
+    /// The task that panicked.
+    task: Option<Task>,
+}
+
+impl Panic {
+    /// Takes the error out of this panic, marking it as observed.
+    pub fn into_error(mut self) -> JoinError {
+        self.err.take().unwrap()
+    }
+}
+
+impl Drop for Panic {
+    fn drop(&mut self) {
+        if let (Some(err), Some(task)) = (self.err.take(), self.task.as_ref()) {
+            // A panicking hook would unwind into the task's internals, so abort instead.
+            abort_on_panic(|| match HOOK.read().unwrap().as_ref() {
+                Some(hook) => hook(task, &err),
+                None => default_hook(task, &err),
+            });
+        }
+    }
+}
+
+pin_project! {
+    /// A future that catches panics while polling the inner future.
+    pub(crate) struct CatchUnwind<F> {
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F> CatchUnwind<F> {
+    /// Wraps a future.
+    pub fn new(future: F) -> CatchUnwind<F> {
+        CatchUnwind { future }
+    }
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Panic>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.project().future;
+
+        match panic::catch_unwind(AssertUnwindSafe(|| future.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(val)) => Poll::Ready(Ok(val)),
+            Err(payload) => Poll::Ready(Err(Panic {
+                err: Some(JoinError::panic(payload)),
+                task: Task::get_current(|t| t.clone()),
+            })),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/// The scheduling priority of a task.
+///
+/// Whenever a worker thread looks for the next task to run, it picks tasks of higher priority
+/// first, both from its own queue and when stealing from other worker threads. Tasks of the same
+/// priority run in the order they were woken up.
+///
+/// Priorities are strict: as long as higher-priority tasks keep the executor busy, tasks of lower
+/// priority don't get to run.
+///
+/// The priority is configured with [`Builder::priority`]. Tasks spawned with [`task::spawn`]
+/// have the `Normal` priority.
+///
+/// [`Builder::priority`]: struct.Builder.html#method.priority
+/// [`task::spawn`]: fn.spawn.html
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Priority {
+    /// For latency-sensitive tasks, like heartbeats.
+    High,
+
+    /// The default priority.
+    Normal,
+
+    /// For background tasks that should only run when the executor is otherwise idle.
+    Low,
+}
+
+impl Priority {
+    /// All priorities, from the highest to the lowest.
+    pub(crate) const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    /// Returns the index of the priority's queues, starting at zero for the highest priority.
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::Normal
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::task::{Task, TaskId};
+
+/// Set to `true` once the registry is enabled.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Tasks spawned while the registry is enabled that are still alive.
+static TASKS: Lazy<Mutex<HashMap<TaskId, Task>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Starts recording spawned tasks in the task registry.
+///
+/// Only tasks spawned after this call are recorded. The registry is disabled by default because
+/// recording tasks adds some overhead to spawning and running them.
+///
+/// Use [`dump`] to list the recorded tasks.
+///
+/// [`dump`]: fn.dump.html
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub fn enable_registry() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Returns a snapshot of all live tasks recorded by the task registry.
+///
+/// The dump is empty unless the registry has been enabled with [`enable_registry`].
+///
+/// Taking a dump locks the registry and allocates, so it must not be called from a signal
+/// handler. To dump the tasks on a signal, have the handler forward the signal to a task or thread
+/// instead (for example through the pipe registered with `signal_hook::low_level::pipe`) and call
+/// this function from there.
+///
+/// [`enable_registry`]: fn.enable_registry.html
+///
+/// # Examples
+///
+/// Print all pending tasks, for example from a debug endpoint:
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use async_std::future;
+/// use async_std::task;
+///
+/// task::enable_registry();
+/// let handle = task::Builder::new()
+///     .name("stuck".to_string())
+///     .spawn(future::pending::<()>())
+///     .unwrap();
+///
+/// let dump = task::dump();
+/// assert!(dump.tasks().iter().any(|t| t.name() == Some("stuck")));
+/// eprintln!("{}", dump);
+/// #
+/// # })
+/// ```
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+pub fn dump() -> TaskDump {
+    let tasks = TASKS.lock().unwrap();
+    let now = Instant::now();
+
+    let mut tasks: Vec<TaskInfo> = tasks
+        .values()
+        .filter_map(|task| {
+            task.record().map(|r| TaskInfo {
+                id: task.id(),
+                name: task.name().map(String::from),
+                parent_id: r.parent_id,
+                location: r.location,
+                state: r.state(),
+                age: now.saturating_duration_since(r.spawned_at),
+            })
+        })
+        .collect();
+    tasks.sort_by_key(|t| t.id.0);
+
+    TaskDump { tasks }
+}
+
+/// What the registry knows about a task.
+#[derive(Debug)]
+pub(crate) struct Record {
+    /// The task that spawned this one.
+    parent_id: Option<TaskId>,
+
+    /// Where the task was spawned.
+    location: &'static Location<'static>,
+
+    /// When the task was spawned.
+    spawned_at: Instant,
+
+    /// The current `TaskState`.
+    state: AtomicU8,
+}
+
+impl Record {
+    /// Creates a record for a task being spawned at the given location.
+    ///
+    /// Returns `None` if the registry is disabled.
+    pub fn new(location: &'static Location<'static>) -> Option<Record> {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        Some(Record {
+            parent_id: Task::get_current(|t| t.id()),
+            location,
+            spawned_at: Instant::now(),
+            state: AtomicU8::new(TaskState::Scheduled as u8),
+        })
+    }
+
+    /// Marks the task as scheduled for running.
+    pub fn scheduled(&self) {
+        self.state
+            .store(TaskState::Scheduled as u8, Ordering::Relaxed);
+    }
+
+    /// Marks the task as being polled.
+    pub fn running(&self) {
+        self.state
+            .store(TaskState::Running as u8, Ordering::Relaxed);
+    }
+
+    /// Marks the task as idle after being polled, unless it was woken up in the meantime.
+    pub fn idle(&self) {
+        let _ = self.state.compare_exchange(
+            TaskState::Running as u8,
+            TaskState::Idle as u8,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Returns the current state of the task.
+    fn state(&self) -> TaskState {
+        match self.state.load(Ordering::Relaxed) {
+            s if s == TaskState::Scheduled as u8 => TaskState::Scheduled,
+            s if s == TaskState::Running as u8 => TaskState::Running,
+            _ => TaskState::Idle,
+        }
+    }
+}
+
+/// Keeps a task in the registry until dropped along with the task's future.
+pub(crate) struct Registration(TaskId);
+
+impl Registration {
+    /// Adds a task to the registry if it has a record.
+    pub fn new(task: &Task) -> Option<Registration> {
+        task.record()?;
+        TASKS.lock().unwrap().insert(task.id(), task.clone());
+        Some(Registration(task.id()))
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Move the task out before dropping it so that the lock isn't held while its inner
+        // representation is deallocated.
+        let task = TASKS.lock().unwrap().remove(&self.0);
+        drop(task);
+    }
+}
+
+/// A snapshot of live tasks, created by [`dump`].
+///
+/// The `Display` implementation prints one line per task.
+///
+/// [`dump`]: fn.dump.html
+#[derive(Clone, Debug)]
+pub struct TaskDump {
+    tasks: Vec<TaskInfo>,
+}
+
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+impl TaskDump {
+    /// Returns the recorded tasks, ordered by their ids.
+    pub fn tasks(&self) -> &[TaskInfo] {
+        &self.tasks
+    }
+}
+
+impl fmt::Display for TaskDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} live tasks:", self.tasks.len())?;
+        for task in &self.tasks {
+            writeln!(f, "  {}", task)?;
+        }
+        Ok(())
+    }
+}
+
+/// Information about a live task.
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+    id: TaskId,
+    name: Option<String>,
+    parent_id: Option<TaskId>,
+    location: &'static Location<'static>,
+    state: TaskState,
+    age: Duration,
+}
+
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+impl TaskInfo {
+    /// Returns the task's id.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Returns the task's name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the id of the task that spawned this one.
+    ///
+    /// Returns `None` if the task was spawned from outside of any task.
+    pub fn parent_id(&self) -> Option<TaskId> {
+        self.parent_id
+    }
+
+    /// Returns the location in the source code where the task was spawned.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the state the task was in when the dump was taken.
+    pub fn state(&self) -> TaskState {
+        self.state
+    }
+
+    /// Returns how long ago the task was spawned.
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+}
+
+impl fmt::Display for TaskInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {}", self.id)?;
+        if let Some(name) = &self.name {
+            write!(f, " {:?}", name)?;
+        }
+        write!(
+            f,
+            " ({}, age {:?}) spawned at {}",
+            self.state, self.age, self.location
+        )?;
+        if let Some(parent_id) = self.parent_id {
+            write!(f, " by task {}", parent_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// The state of a live task.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TaskState {
+    /// The task has been woken up and is waiting to be polled.
+    Scheduled,
+
+    /// The task is being polled.
+    Running,
+
+    /// The task is waiting to be woken up.
+    Idle,
+}
+
+impl fmt::Display for TaskState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskState::Scheduled => "scheduled".fmt(f),
+            TaskState::Running => "running".fmt(f),
+            TaskState::Idle => "idle".fmt(f),
+        }
+    }
+}
@@ -0,0 +1,453 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::io;
+use crate::net::driver;
+use crate::task::executor::{self, Config, Pool};
+use crate::task::{spawn_blocking, Builder, JoinHandle, Metrics};
+
+/// Runtime builder that configures the global executor.
+///
+/// The global executor is started lazily, when the first task is spawned. The configuration
+/// has to be applied before that happens, typically at the very beginning of `main`.
+///
+/// # Examples
+///
+/// ```
+/// use async_std::task::RuntimeBuilder;
+///
+/// let rt = RuntimeBuilder::new()
+///     .num_threads(2)
+///     .thread_name("my-executor".to_string())
+///     .build()
+///     .expect("the runtime has already started");
+///
+/// assert_eq!(rt.num_threads(), 2);
+/// ```
+#[derive(Debug)]
+pub struct RuntimeBuilder {
+    config: Config,
+}
+
+impl RuntimeBuilder {
+    /// Creates a new builder with the default configuration.
+    ///
+    /// By default, the executor spawns one worker thread per logical CPU.
+    #[inline]
+    pub fn new() -> RuntimeBuilder {
+        RuntimeBuilder {
+            config: Config::default(),
+        }
+    }
+
+    /// Configures the number of worker threads.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `num_threads` is zero.
+    pub fn num_threads(mut self, num_threads: usize) -> RuntimeBuilder {
+        assert!(
+            num_threads > 0,
+            "the number of worker threads must be positive"
+        );
+        self.config.num_threads = num_threads;
+        self
+    }
+
+    /// Configures the name of the worker threads.
+    ///
+    /// The default name is `"async-std/executor"`.
+    #[inline]
+    pub fn thread_name(mut self, name: String) -> RuntimeBuilder {
+        self.config.thread_name = name;
+        self
+    }
+
+    /// Configures the stack size (in bytes) of the worker threads.
+    ///
+    /// By default, the platform's default stack size for new threads is used.
+    #[inline]
+    pub fn stack_size(mut self, size: usize) -> RuntimeBuilder {
+        self.config.stack_size = Some(size);
+        self
+    }
+
+    /// Registers a function called on every worker thread right after it starts.
+    pub fn on_thread_start<F>(mut self, f: F) -> RuntimeBuilder
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.config.on_thread_start = Some(Arc::new(f));
+        self
+    }
+
+    /// Registers a function called on every worker thread right before it stops.
+    pub fn on_thread_stop<F>(mut self, f: F) -> RuntimeBuilder
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.config.on_thread_stop = Some(Arc::new(f));
+        self
+    }
+
+    /// Configures the maximum number of threads in the blocking pool.
+    ///
+    /// Threads in the blocking pool run tasks spawned with [`spawn_blocking`], including file
+    /// system operations. When all of them are busy, new tasks wait in a queue.
+    ///
+    /// The default limit is 512 threads. The blocking pool is shared by all executors, so this
+    /// setting only takes effect when configuring the global runtime with [`build`].
+    ///
+    /// [`spawn_blocking`]: fn.spawn_blocking.html
+    /// [`build`]: #method.build
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `max` is zero.
+    pub fn max_blocking_threads(mut self, max: usize) -> RuntimeBuilder {
+        assert!(max > 0, "the number of blocking threads must be positive");
+        self.config.max_blocking_threads = max;
+        self
+    }
+
+    /// Configures how long an idle thread in the blocking pool waits for a new task before
+    /// stopping.
+    ///
+    /// The default is one second.
+    #[inline]
+    pub fn blocking_keep_alive(mut self, keep_alive: Duration) -> RuntimeBuilder {
+        self.config.blocking_keep_alive = keep_alive;
+        self
+    }
+
+    /// Configures the name of the threads in the blocking pool.
+    ///
+    /// The default name is `"async-std/blocking"`.
+    #[inline]
+    pub fn blocking_thread_name(mut self, name: String) -> RuntimeBuilder {
+        self.config.blocking_thread_name = name;
+        self
+    }
+
+    /// Configures how many tasks can wait for a thread in the blocking pool before
+    /// [`try_spawn_blocking`] starts failing.
+    ///
+    /// By default, the queue is unbounded. [`spawn_blocking`] always queues the task regardless
+    /// of this setting.
+    ///
+    /// [`try_spawn_blocking`]: fn.try_spawn_blocking.html
+    /// [`spawn_blocking`]: fn.spawn_blocking.html
+    #[inline]
+    pub fn blocking_queue_capacity(mut self, capacity: usize) -> RuntimeBuilder {
+        self.config.blocking_queue_capacity = Some(capacity);
+        self
+    }
+
+    /// Configures the number of shards in the networking driver.
+    ///
+    /// Every shard polls its own share of the I/O handles, and idle worker threads poll a shard
+    /// directly instead of waiting for a driver thread to wake them up. Each shard has its own
+    /// driver thread, so sharding is opt-in: by default, there is a single shard. Using one shard
+    /// per worker thread spreads the load on I/O-heavy workloads. The networking driver is shared
+    /// by all executors, so this setting only takes effect when configuring the global runtime
+    /// with [`build`].
+    ///
+    /// [`build`]: #method.build
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `shards` is zero.
+    pub fn reactor_shards(mut self, shards: usize) -> RuntimeBuilder {
+        assert!(shards > 0, "the number of reactor shards must be positive");
+        self.config.reactor_shards = shards;
+        self
+    }
+
+    /// Applies the configuration to the global executor.
+    ///
+    /// This method returns an error if the global executor has already been configured, or if
+    /// the default configuration has already been locked in. That happens on the first call that
+    /// starts the global executor or the networking driver:
+    ///
+    /// * spawning a task onto the global executor, e.g. with [`task::spawn`],
+    /// * taking a snapshot with [`task::metrics`],
+    /// * using a network I/O handle or a timer, e.g. with [`task::sleep`],
+    /// * reading or writing a file when the `io-uring` feature is enabled.
+    ///
+    /// The blocking pool doesn't lock in the configuration, so [`spawn_blocking`] and file system
+    /// operations without `io-uring` can run before this method is called. Threads the blocking
+    /// pool starts afterwards use the new configuration.
+    ///
+    /// [`task::spawn`]: fn.spawn.html
+    /// [`task::metrics`]: fn.metrics.html
+    /// [`task::sleep`]: fn.sleep.html
+    /// [`spawn_blocking`]: fn.spawn_blocking.html
+    pub fn build(self) -> io::Result<Runtime> {
+        executor::configure(self.config)?;
+        Ok(Runtime { _private: () })
+    }
+
+    /// Creates a new executor with the configured settings.
+    ///
+    /// Unlike [`build`], this method doesn't touch the global executor. Instead, it starts a
+    /// separate pool of worker threads owned by the returned [`Executor`].
+    ///
+    /// [`build`]: #method.build
+    /// [`Executor`]: struct.Executor.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use async_std::task::{self, RuntimeBuilder};
+    ///
+    /// let executor = RuntimeBuilder::new()
+    ///     .num_threads(1)
+    ///     .thread_name("batch".to_string())
+    ///     .build_executor()
+    ///     .unwrap();
+    ///
+    /// let handle = executor.spawn(async { 1 + 2 });
+    /// assert_eq!(task::block_on(handle), 3);
+    /// ```
+    pub fn build_executor(self) -> io::Result<Executor> {
+        let pool = Pool::new(self.config)?;
+        Ok(Executor {
+            handle: Handle { pool },
+        })
+    }
+}
+
+impl Default for RuntimeBuilder {
+    fn default() -> RuntimeBuilder {
+        RuntimeBuilder::new()
+    }
+}
+
+/// A handle to the global runtime.
+///
+/// Created by [`RuntimeBuilder::build`].
+///
+/// [`RuntimeBuilder::build`]: struct.RuntimeBuilder.html#method.build
+#[derive(Debug)]
+pub struct Runtime {
+    _private: (),
+}
+
+impl Runtime {
+    /// Returns the number of worker threads in the global executor.
+    pub fn num_threads(&self) -> usize {
+        executor::config().num_threads
+    }
+
+    /// Returns the name of the worker threads in the global executor.
+    pub fn thread_name(&self) -> &str {
+        &executor::config().thread_name
+    }
+
+    /// Spawns a task onto the global executor.
+    ///
+    /// This is equivalent to [`task::spawn`].
+    ///
+    /// [`task::spawn`]: fn.spawn.html
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        crate::task::spawn(future)
+    }
+
+    /// Blocks the current thread on a future's result.
+    ///
+    /// This is equivalent to [`task::block_on`].
+    ///
+    /// [`task::block_on`]: fn.block_on.html
+    pub fn block_on<F, T>(&self, future: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        crate::task::block_on(future)
+    }
+
+    /// Shuts the global runtime down, waiting up to `timeout` for spawned tasks to complete.
+    ///
+    /// The global executor stops accepting new tasks right away, so [`task::spawn`] panics from
+    /// then on. The only exception are tasks running on the global executor, which may still
+    /// spawn new tasks while this method waits for them. Tasks that are still alive when the
+    /// timeout elapses are cancelled. Then this method joins the worker threads, the threads in
+    /// the blocking pool, and the thread driving network I/O.
+    ///
+    /// A blocking task that is still running at the deadline can't be interrupted, so its thread
+    /// is left to finish in the background. The global runtime cannot be restarted afterwards.
+    ///
+    /// [`task::spawn`]: fn.spawn.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use async_std::task::RuntimeBuilder;
+    ///
+    /// let rt = RuntimeBuilder::new().build().unwrap();
+    /// rt.spawn(async {
+    ///     // Serve requests...
+    /// });
+    ///
+    /// // Give in-flight tasks a second to finish before exiting.
+    /// rt.shutdown_timeout(Duration::from_secs(1));
+    /// ```
+    pub fn shutdown_timeout(self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        executor::shutdown(deadline);
+        spawn_blocking::shutdown(deadline);
+        driver::shutdown();
+    }
+}
+
+/// An executor with its own pool of worker threads.
+///
+/// Tasks spawned onto an executor only ever run on its worker threads, which makes it possible to
+/// isolate groups of tasks from each other and from the global executor used by [`task::spawn`].
+///
+/// Dropping the executor stops its worker threads. Tasks that haven't completed by then are
+/// cancelled. To give them time to complete, use [`shutdown_timeout`] instead.
+///
+/// [`task::spawn`]: fn.spawn.html
+/// [`shutdown_timeout`]: #method.shutdown_timeout
+///
+/// # Examples
+///
+/// ```
+/// use async_std::task::{self, Executor};
+///
+/// let executor = Executor::new().unwrap();
+/// let handle = executor.handle();
+///
+/// let task = handle.spawn(async { 1 + 2 }).unwrap();
+/// assert_eq!(task::block_on(task), 3);
+/// ```
+#[derive(Debug)]
+pub struct Executor {
+    handle: Handle,
+}
+
+impl Executor {
+    /// Creates a new executor with the default configuration.
+    ///
+    /// Use [`RuntimeBuilder::build_executor`] to configure the executor's worker threads.
+    ///
+    /// [`RuntimeBuilder::build_executor`]: struct.RuntimeBuilder.html#method.build_executor
+    pub fn new() -> io::Result<Executor> {
+        RuntimeBuilder::new().build_executor()
+    }
+
+    /// Returns a handle for spawning tasks onto this executor.
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Returns the number of worker threads in this executor.
+    pub fn num_threads(&self) -> usize {
+        self.handle.pool.config().num_threads
+    }
+
+    /// Returns a snapshot of this executor's counters and queue depths.
+    pub fn metrics(&self) -> Metrics {
+        self.handle.pool.metrics().with_blocking_pool()
+    }
+
+    /// Spawns a task onto this executor.
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.handle.spawn(future).expect("cannot spawn task")
+    }
+
+    /// Shuts the executor down, waiting up to `timeout` for its tasks to complete.
+    ///
+    /// The executor stops accepting new tasks right away, so spawning through a [`Handle`]
+    /// returns an error from then on. The only exception are tasks running on the executor, which
+    /// may still spawn new tasks while this method waits for them. Tasks that are still alive
+    /// when the timeout elapses are cancelled, and this method returns once the worker threads
+    /// have stopped.
+    ///
+    /// A task can't be interrupted while it is being polled, so a task that blocks its worker
+    /// thread delays the shutdown until it yields.
+    ///
+    /// [`Handle`]: struct.Handle.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use async_std::future;
+    /// use async_std::task::{self, Executor};
+    ///
+    /// let executor = Executor::new().unwrap();
+    /// let done = executor.spawn(async { 1 + 2 });
+    /// let stuck = executor.spawn(future::pending::<()>());
+    ///
+    /// executor.shutdown_timeout(Duration::from_millis(10));
+    ///
+    /// assert_eq!(task::block_on(done), 3);
+    /// assert_eq!(task::block_on(stuck.cancel()), None);
+    /// ```
+    pub fn shutdown_timeout(self, timeout: Duration) {
+        self.handle.pool.shutdown(Instant::now() + timeout);
+    }
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        self.handle.pool.close();
+    }
+}
+
+/// A handle for spawning tasks onto an [`Executor`].
+///
+/// Handles are cheap to clone and can be sent to other threads.
+///
+/// [`Executor`]: struct.Executor.html
+#[derive(Clone)]
+pub struct Handle {
+    pool: Arc<Pool>,
+}
+
+impl Handle {
+    /// Spawns a task onto the executor.
+    ///
+    /// This method returns an error if the executor has been shut down or dropped.
+    ///
+    /// To configure the spawned task, use [`Builder::spawn_on`] instead.
+    ///
+    /// [`Builder::spawn_on`]: struct.Builder.html#method.spawn_on
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> io::Result<JoinHandle<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        Builder::new().spawn_on(self, future)
+    }
+
+    /// Returns the executor this handle points to.
+    pub(crate) fn pool(&self) -> &Arc<Pool> {
+        &self.pool
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("config", self.pool.config())
+            .finish()
+    }
+}
@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_utils::sync::Unparker;
+
+use crate::task::block_on::block_on_with;
+use crate::task::{Builder, JoinHandle, Runnable};
+use crate::timer::Clock;
+use crate::utils::{random, seed_random};
+
+thread_local! {
+    /// The queue of the simulation running on the current thread.
+    static CURRENT: RefCell<Option<Arc<SimQueue>>> = RefCell::new(None);
+}
+
+/// A single-threaded runtime with a virtual clock for reproducible tests.
+///
+/// Tasks spawned with [`task::spawn`] from within [`Simulation::block_on`] run on the current
+/// thread instead of the global executor. Every time a task needs to be picked, the simulation
+/// picks one of the runnable tasks at random, using a generator seeded with the seed passed to
+/// [`Simulation::new`]. The same seed always produces the same schedule, so a failure found with
+/// one seed can be replayed by running the simulation with that seed again. The seed is printed
+/// to standard error if [`Simulation::block_on`] panics.
+///
+/// Timers created within the simulation, like [`task::sleep`], [`future::timeout`],
+/// [`io::timeout`], and [`stream::interval`], follow a virtual clock. When all tasks are idle,
+/// the clock jumps straight to the earliest pending timer, so sleeping takes no real time.
+///
+/// If all tasks are idle and no timers are pending, the current thread blocks until a task is
+/// woken up by another thread, for example by a task spawned with [`spawn_blocking`].
+///
+/// Dropping the simulation cancels the tasks that haven't completed yet.
+///
+/// [`task::spawn`]: fn.spawn.html
+/// [`Simulation::block_on`]: #method.block_on
+/// [`Simulation::new`]: #method.new
+/// [`task::sleep`]: fn.sleep.html
+/// [`future::timeout`]: ../future/fn.timeout.html
+/// [`io::timeout`]: ../io/fn.timeout.html
+/// [`stream::interval`]: ../stream/fn.interval.html
+/// [`spawn_blocking`]: fn.spawn_blocking.html
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use async_std::task::{self, Simulation};
+///
+/// let sim = Simulation::new(42);
+/// let start = sim.now();
+///
+/// sim.block_on(async {
+///     let handle = task::spawn(async {
+///         task::sleep(Duration::from_secs(3600)).await;
+///         1
+///     });
+///     assert_eq!(handle.await, 1);
+/// });
+///
+/// // An hour has passed on the virtual clock.
+/// assert_eq!(sim.now() - start, Duration::from_secs(3600));
+/// ```
+#[derive(Debug)]
+pub struct Simulation {
+    /// The seed of the scheduling order.
+    seed: u64,
+
+    /// The virtual clock followed by timers.
+    clock: Arc<Clock>,
+
+    /// The queue of runnable tasks.
+    queue: Arc<SimQueue>,
+
+    /// Makes the type `!Send` and `!Sync`.
+    _marker: PhantomData<Rc<()>>,
+}
+
+impl Simulation {
+    /// Creates a new simulation with the given seed.
+    pub fn new(seed: u64) -> Simulation {
+        Simulation {
+            seed,
+            clock: Clock::new(),
+            queue: Arc::new(SimQueue {
+                tasks: Mutex::new(Vec::new()),
+                unparker: Mutex::new(None),
+                closed: AtomicBool::new(false),
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the seed of this simulation.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the current time on the virtual clock.
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Spawns a task onto this simulation.
+    ///
+    /// The task will start running once the current thread calls [`block_on`].
+    ///
+    /// [`block_on`]: #method.block_on
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        Builder::new().spawn_simulated(self.queue.clone(), future)
+    }
+
+    /// Blocks the current thread on a future's result while running the simulation.
+    ///
+    /// The random number generator is reseeded on every call, so each call with the same
+    /// spawned tasks runs the same way.
+    pub fn block_on<F, T>(&self, future: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        /// Prints the seed if the simulation panics.
+        struct Report(u64);
+
+        impl Drop for Report {
+            fn drop(&mut self) {
+                if thread::panicking() {
+                    eprintln!("simulation failed with seed {}", self.0);
+                }
+            }
+        }
+
+        let _report = Report(self.seed);
+        seed_random(self.seed);
+
+        // Make this simulation the current one while the future is running.
+        let prev = CURRENT.with(|c| c.replace(Some(self.queue.clone())));
+        defer! {
+            CURRENT.with(|c| *c.borrow_mut() = prev);
+        }
+
+        let mut registered = false;
+        let res = self.clock.enter(|| {
+            block_on_with(future, |unparker| {
+                // Make sure scheduling a task wakes up the current thread.
+                if !registered {
+                    *self.queue.unparker.lock().unwrap() = Some(unparker.clone());
+                    registered = true;
+                }
+
+                // Run one task at a time so that the future is polled in between, or advance
+                // the clock if all tasks are idle.
+                match self.queue.pop() {
+                    Some(task) => {
+                        task.run();
+                        true
+                    }
+                    None => self.clock.advance(),
+                }
+            })
+        });
+
+        *self.queue.unparker.lock().unwrap() = None;
+        res
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.queue.closed.store(true, Ordering::SeqCst);
+
+        // Cancel the tasks in the queue, and the tasks waiting on timers.
+        let tasks = mem::take(&mut *self.queue.tasks.lock().unwrap());
+        drop(tasks);
+        self.clock.wake_all();
+    }
+}
+
+/// The queue of tasks in a simulation.
+pub(crate) struct SimQueue {
+    /// Tasks that are ready to run.
+    tasks: Mutex<Vec<Runnable>>,
+
+    /// Wakes up the thread running the simulation.
+    unparker: Mutex<Option<Unparker>>,
+
+    /// Set to `true` when the simulation is dropped.
+    closed: AtomicBool,
+}
+
+impl SimQueue {
+    /// Returns the queue of the simulation running on the current thread.
+    pub fn current() -> Option<Arc<SimQueue>> {
+        CURRENT.try_with(|c| c.borrow().clone()).ok().flatten()
+    }
+
+    /// Schedules a task for execution.
+    pub fn schedule(&self, task: Runnable) {
+        // Dropping the task cancels it if the simulation has been dropped.
+        if self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        self.tasks.lock().unwrap().push(task);
+        if let Some(unparker) = self.unparker.lock().unwrap().as_ref() {
+            unparker.unpark();
+        }
+    }
+
+    /// Pops a random task from the queue.
+    fn pop(&self) -> Option<Runnable> {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.is_empty() {
+            return None;
+        }
+        let i = random(tasks.len() as u32) as usize;
+        Some(tasks.swap_remove(i))
+    }
+}
+
+impl fmt::Debug for SimQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimQueue")
+            .field("tasks", &self.tasks.lock().unwrap().len())
+            .field("closed", &self.closed.load(Ordering::SeqCst))
+            .finish()
+    }
+}
@@ -1,7 +1,11 @@
+use std::future::Future;
+use std::pin::Pin;
 use std::time::Duration;
+#[cfg(feature = "unstable")]
+use std::time::Instant;
 
-use crate::future;
-use crate::io;
+use crate::task::{budget, Context, Poll};
+use crate::timer::Timer;
 
 /// Sleeps for the specified amount of time.
 ///
@@ -28,6 +32,110 @@ use crate::io;
 /// #
 /// # })
 /// ```
-pub async fn sleep(dur: Duration) {
-    let _: io::Result<()> = io::timeout(dur, future::pending()).await;
+pub fn sleep(dur: Duration) -> Sleep {
+    Sleep {
+        timer: Timer::new(dur),
+    }
+}
+
+/// Sleeps until the specified point in time.
+///
+/// This function might sleep for slightly longer than until the deadline but never less. If the
+/// deadline has already passed, the returned future completes immediately.
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use std::time::{Duration, Instant};
+///
+/// use async_std::task;
+///
+/// let deadline = Instant::now() + Duration::from_millis(10);
+/// task::sleep_until(deadline).await;
+/// assert!(Instant::now() >= deadline);
+/// #
+/// # })
+/// ```
+#[cfg(feature = "unstable")]
+#[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep {
+        timer: Timer::at(deadline),
+    }
+}
+
+/// A future that completes at a point in time.
+///
+/// This future is created by the [`sleep`] and [`sleep_until`] functions. The deadline can be
+/// moved with [`reset`] without registering a new timer, which makes it cheap to push idle
+/// timeouts forward every time there is activity.
+///
+/// [`sleep`]: fn.sleep.html
+/// [`sleep_until`]: fn.sleep_until.html
+/// [`reset`]: #method.reset
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    timer: Timer,
+}
+
+impl Sleep {
+    /// Returns the point in time this future completes at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use async_std::task;
+    ///
+    /// let deadline = Instant::now() + Duration::from_secs(1);
+    /// assert_eq!(task::sleep_until(deadline).deadline(), deadline);
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn deadline(&self) -> Instant {
+        self.timer.deadline()
+    }
+
+    /// Changes the point in time this future completes at.
+    ///
+    /// This can be called even after the future has completed, in which case it can be awaited
+    /// again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use async_std::task;
+    ///
+    /// let mut sleep = task::sleep(Duration::from_millis(10));
+    /// (&mut sleep).await;
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// sleep.reset(deadline);
+    /// (&mut sleep).await;
+    /// assert!(Instant::now() >= deadline);
+    /// #
+    /// # })
+    /// ```
+    #[cfg(feature = "unstable")]
+    #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
+    pub fn reset(&mut self, deadline: Instant) {
+        self.timer.reset(deadline);
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        futures_core::ready!(budget::poll_proceed(cx));
+        Pin::new(&mut self.timer).poll(cx)
+    }
 }
@@ -8,6 +8,15 @@ use crate::task::{Builder, JoinHandle};
 ///
 /// [`std::thread`]: https://doc.rust-lang.org/std/thread/fn.spawn.html
 ///
+/// # Panics
+///
+/// Panics if the global executor can't start its worker threads, or if it is being shut down
+/// with [`Runtime::shutdown_timeout`] and the caller is not one of its tasks. Use
+/// [`Builder::spawn`] to handle these errors instead.
+///
+/// [`Runtime::shutdown_timeout`]: struct.Runtime.html#method.shutdown_timeout
+/// [`Builder::spawn`]: struct.Builder.html#method.spawn
+///
 /// # Examples
 ///
 /// ```
@@ -23,6 +32,7 @@ use crate::task::{Builder, JoinHandle};
 /// #
 /// # })
 /// ```
+#[track_caller]
 pub fn spawn<F, T>(future: F) -> JoinHandle<T>
 where
     F: Future<Output = T> + Send + 'static,
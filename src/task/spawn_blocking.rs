@@ -1,10 +1,17 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
 use std::thread;
-use std::time::Duration;
+#[cfg(feature = "unstable")]
+use std::time::Instant;
 
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{select, unbounded, Receiver, Sender};
 use once_cell::sync::Lazy;
 
+use crate::io;
+use crate::task::executor;
+use crate::task::panic::CatchUnwind;
+use crate::task::registry::{Record, Registration};
 use crate::task::{JoinHandle, Task};
 use crate::utils::abort_on_panic;
 
@@ -14,8 +21,20 @@ use crate::utils::abort_on_panic;
 /// is useful to prevent long-running synchronous operations from blocking the main futures
 /// executor.
 ///
+/// The pool starts new threads as needed, up to a limit configured with
+/// [`RuntimeBuilder::max_blocking_threads`]. Once all threads are busy, tasks wait in a queue
+/// until a thread becomes available. To avoid piling up tasks in the queue, use
+/// [`try_spawn_blocking`] instead.
+///
 /// See also: [`task::block_on`], [`task::spawn`].
 ///
+/// # Panics
+///
+/// Panics if the pool has no threads and a new one can't be started. Use [`try_spawn_blocking`]
+/// to handle that error instead.
+///
+/// [`RuntimeBuilder::max_blocking_threads`]: struct.RuntimeBuilder.html#method.max_blocking_threads
+/// [`try_spawn_blocking`]: fn.try_spawn_blocking.html
 /// [`task::block_on`]: fn.block_on.html
 /// [`task::spawn`]: fn.spawn.html
 ///
@@ -37,13 +56,82 @@ use crate::utils::abort_on_panic;
 /// ```
 #[cfg_attr(feature = "docs", doc(cfg(unstable)))]
 #[inline]
+#[track_caller]
 pub fn spawn_blocking<F, T>(f: F) -> JoinHandle<T>
 where
     F: FnOnce() -> T + Send + 'static,
     T: Send + 'static,
 {
-    let schedule = |task| POOL.sender.send(task).unwrap();
-    let (task, handle) = async_task::spawn(async { f() }, schedule, Task::new(None));
+    spawn(f, Location::caller())
+}
+
+/// Spawns a blocking task, unless the blocking thread pool is saturated.
+///
+/// This function is similar to [`spawn_blocking`], except it returns an error of kind
+/// [`WouldBlock`] instead of queueing the task if the queue of tasks waiting for a thread has
+/// reached the capacity configured with [`blocking_queue_capacity`]. The error is a signal to
+/// shed load or to retry later.
+///
+/// If no capacity is configured, the task is always spawned. An error is also returned if the
+/// pool needs a new thread for the task and the thread can't be started.
+///
+/// [`spawn_blocking`]: fn.spawn_blocking.html
+/// [`WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+/// [`blocking_queue_capacity`]: struct.RuntimeBuilder.html#method.blocking_queue_capacity
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use async_std::io;
+/// use async_std::task;
+///
+/// match task::try_spawn_blocking(|| 1 + 2) {
+///     Ok(handle) => assert_eq!(handle.await, 3),
+///     Err(err) if err.kind() == io::ErrorKind::WouldBlock => println!("too busy, try later"),
+///     Err(err) => panic!("{}", err),
+/// }
+/// #
+/// # })
+/// ```
+#[cfg(feature = "unstable")]
+#[track_caller]
+pub fn try_spawn_blocking<F, T>(f: F) -> io::Result<JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    if let Some(capacity) = executor::current_config().blocking_queue_capacity {
+        if POOL.receiver.len() >= capacity {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "the blocking thread pool is saturated",
+            ));
+        }
+    }
+
+    // Start a thread up front, so that failing to start it is returned rather than a panic.
+    if SLEEPING.load(Ordering::SeqCst) == 0 {
+        start_thread()?;
+    }
+    Ok(spawn(f, Location::caller()))
+}
+
+/// Spawns a blocking task that was spawned at the given location.
+fn spawn<F, T>(f: F, location: &'static Location<'static>) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let task = Task::new(None, Record::new(location));
+    let registration = Registration::new(&task);
+
+    let future = CatchUnwind::new(async {
+        let _registration = registration;
+        f()
+    });
+    let (task, handle) = async_task::spawn(future, schedule, task);
     task.schedule();
     JoinHandle::new(handle)
 }
@@ -53,69 +141,192 @@ type Runnable = async_task::Task<Task>;
 /// The number of sleeping worker threads.
 static SLEEPING: AtomicUsize = AtomicUsize::new(0);
 
+/// The number of worker threads, both sleeping and running tasks.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of threads in the blocking pool.
+#[cfg(feature = "unstable")]
+pub(crate) fn num_threads() -> usize {
+    THREADS.load(Ordering::SeqCst)
+}
+
+/// Returns the number of idle threads in the blocking pool.
+#[cfg(feature = "unstable")]
+pub(crate) fn num_idle_threads() -> usize {
+    SLEEPING.load(Ordering::SeqCst)
+}
+
+/// Returns the number of blocking tasks waiting for a thread.
+#[cfg(feature = "unstable")]
+pub(crate) fn queue_depth() -> usize {
+    POOL.receiver.len()
+}
+
 struct Pool {
     sender: Sender<Runnable>,
     receiver: Receiver<Runnable>,
+
+    /// Dropped on shutdown to wake up idle threads.
+    stop_sender: Mutex<Option<Sender<()>>>,
+
+    /// Becomes disconnected on shutdown.
+    stop: Receiver<()>,
+
+    /// Notified when a thread stops.
+    stopped: Condvar,
 }
 
 static POOL: Lazy<Pool> = Lazy::new(|| {
-    // Start a single worker thread waiting for the first task.
-    start_thread();
-
     let (sender, receiver) = unbounded();
-    Pool { sender, receiver }
+    let (stop_sender, stop) = unbounded();
+    Pool {
+        sender,
+        receiver,
+        stop_sender: Mutex::new(Some(stop_sender)),
+        stop,
+        stopped: Condvar::new(),
+    }
 });
 
-fn start_thread() {
+/// Set to `true` once the pool has been shut down.
+static CLOSED: AtomicBool = AtomicBool::new(false);
+
+/// Shuts down the blocking pool.
+///
+/// Queued tasks are cancelled right away. Threads running tasks are given until the deadline to
+/// finish them, after which they are left running in the background.
+#[cfg(feature = "unstable")]
+pub(crate) fn shutdown(deadline: Instant) {
+    let mut stop_sender = POOL.stop_sender.lock().unwrap();
+    CLOSED.store(true, Ordering::SeqCst);
+
+    // Wake up idle threads so that they stop.
+    drop(stop_sender.take());
+
+    // Cancel the tasks waiting for a thread.
+    while let Ok(task) = POOL.receiver.try_recv() {
+        drop(task);
+    }
+
+    // Wait for the threads to stop.
+    while THREADS.load(Ordering::SeqCst) > 0 {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        stop_sender = POOL
+            .stopped
+            .wait_timeout(stop_sender, deadline - now)
+            .unwrap()
+            .0;
+    }
+}
+
+/// Queues a task and makes sure a thread is going to pick it up.
+fn schedule(task: Runnable) {
+    // Dropping the task cancels it if the pool has been shut down.
+    if CLOSED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    POOL.sender.send(task).unwrap();
+
+    // If all threads are busy, start a new one.
+    if SLEEPING.load(Ordering::SeqCst) == 0 {
+        ensure_thread();
+    }
+}
+
+/// Starts a new thread to pick up queued tasks.
+///
+/// If the thread can't be started, the tasks wait for one of the running threads instead. This
+/// panics if there is none.
+fn ensure_thread() {
+    if let Err(err) = start_thread() {
+        if THREADS.load(Ordering::SeqCst) == 0 {
+            panic!("cannot start a blocking thread: {}", err);
+        }
+    }
+}
+
+/// Starts a new thread, unless the pool has reached its maximum size.
+fn start_thread() -> io::Result<()> {
+    // The blocking pool doesn't lock in the configuration, so that it can be used before the
+    // global runtime is configured.
+    let config = executor::current_config();
+
+    // Reserve a slot for the new thread.
+    let mut threads = THREADS.load(Ordering::SeqCst);
+    loop {
+        if threads >= config.max_blocking_threads || CLOSED.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        match THREADS.compare_exchange_weak(
+            threads,
+            threads + 1,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => break,
+            Err(t) => threads = t,
+        }
+    }
     SLEEPING.fetch_add(1, Ordering::SeqCst);
-    let timeout = Duration::from_secs(1);
-
-    thread::Builder::new()
-        .name("async-std/blocking".to_string())
-        .spawn(move || {
-            loop {
-                let mut task = match POOL.receiver.recv_timeout(timeout) {
-                    Ok(task) => task,
-                    Err(_) => {
-                        // Check whether this is the last sleeping thread.
-                        if SLEEPING.fetch_sub(1, Ordering::SeqCst) == 1 {
-                            // If so, then restart the thread to make sure there is always at least
-                            // one sleeping thread.
-                            if SLEEPING.compare_and_swap(0, 1, Ordering::SeqCst) == 0 {
-                                continue;
-                            }
-                        }
-
-                        // Stop the thread.
-                        return;
-                    }
-                };
 
-                // If there are no sleeping threads, then start one to make sure there is always at
-                // least one sleeping thread.
-                if SLEEPING.fetch_sub(1, Ordering::SeqCst) == 1 {
-                    start_thread();
-                }
+    let res = thread::Builder::new()
+        .name(config.blocking_thread_name.clone())
+        .spawn(move || loop {
+            let task = select! {
+                recv(POOL.receiver) -> task => task.ok(),
+                recv(POOL.stop) -> _ => None,
+                default(config.blocking_keep_alive) => None,
+            };
 
-                loop {
-                    // Run the task.
-                    abort_on_panic(|| task.run());
+            let task = match task {
+                Some(task) if !CLOSED.load(Ordering::SeqCst) => task,
+                _ => {
+                    // Stop the thread after it has been idle for too long or the pool has been
+                    // shut down. A task received after shutdown is cancelled.
+                    drop(task);
+                    SLEEPING.fetch_sub(1, Ordering::SeqCst);
+                    THREADS.fetch_sub(1, Ordering::SeqCst);
 
-                    // Try taking another task if there are any available.
-                    task = match POOL.receiver.try_recv() {
-                        Ok(task) => task,
-                        Err(_) => break,
-                    };
-                }
+                    // Taking the lock makes sure a concurrent `shutdown` is either waiting for the
+                    // notification or hasn't checked the number of threads yet.
+                    drop(POOL.stop_sender.lock().unwrap());
+                    POOL.stopped.notify_all();
 
-                // If there is at least one sleeping thread, stop this thread instead of putting it
-                // to sleep.
-                if SLEEPING.load(Ordering::SeqCst) > 0 {
+                    // A task may have been queued while this thread was still counted as
+                    // sleeping, so make sure it doesn't get stuck in the queue.
+                    if !POOL.receiver.is_empty() && SLEEPING.load(Ordering::SeqCst) == 0 {
+                        ensure_thread();
+                    }
                     return;
                 }
+            };
 
-                SLEEPING.fetch_add(1, Ordering::SeqCst);
+            // If this was the last sleeping thread and more tasks are waiting, start another
+            // thread to pick them up.
+            if SLEEPING.fetch_sub(1, Ordering::SeqCst) == 1 && !POOL.receiver.is_empty() {
+                ensure_thread();
             }
-        })
-        .expect("cannot start a blocking thread");
+
+            // Run the task.
+            if let Some(record) = task.tag().record() {
+                record.running();
+            }
+            abort_on_panic(|| task.run());
+
+            SLEEPING.fetch_add(1, Ordering::SeqCst);
+        });
+
+    // Give the reserved slot back if the thread couldn't be started.
+    if let Err(err) = res {
+        SLEEPING.fetch_sub(1, Ordering::SeqCst);
+        THREADS.fetch_sub(1, Ordering::SeqCst);
+        drop(POOL.stop_sender.lock().unwrap());
+        POOL.stopped.notify_all();
+        return Err(err);
+    }
+    Ok(())
 }
@@ -5,6 +5,7 @@ use std::ptr;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use std::sync::Arc;
 
+use crate::task::registry::Record;
 use crate::task::{LocalsMap, TaskId};
 use crate::utils::abort_on_panic;
 
@@ -23,15 +24,19 @@ struct Inner {
 
     /// The map holding task-local values.
     locals: LocalsMap,
+
+    /// The task registry's record of this task, if the registry is enabled.
+    record: Option<Arc<Record>>,
 }
 
 impl Inner {
     #[inline]
-    fn new(name: Option<String>) -> Inner {
+    fn new(name: Option<String>, record: Option<Record>) -> Inner {
         Inner {
             id: TaskId::generate(),
             name: name.map(String::into_boxed_str),
             locals: LocalsMap::new(),
+            record: record.map(Arc::new),
         }
     }
 }
@@ -51,14 +56,14 @@ unsafe impl Sync for Task {}
 impl Task {
     /// Creates a new task handle.
     ///
-    /// If the task is unnamed, the inner representation of the task will be lazily allocated on
-    /// demand.
+    /// If the task is unnamed and not recorded by the task registry, the inner representation of
+    /// the task will be lazily allocated on demand.
     #[inline]
-    pub(crate) fn new(name: Option<String>) -> Task {
-        let inner = match name {
-            None => AtomicPtr::default(),
-            Some(name) => {
-                let raw = Arc::into_raw(Arc::new(Inner::new(Some(name))));
+    pub(crate) fn new(name: Option<String>, record: Option<Record>) -> Task {
+        let inner = match (name, record) {
+            (None, None) => AtomicPtr::default(),
+            (name, record) => {
+                let raw = Arc::into_raw(Arc::new(Inner::new(name, record)));
                 AtomicPtr::new(raw as *mut Inner)
             }
         };
@@ -80,6 +85,14 @@ impl Task {
         self.inner().name.as_ref().map(|s| &**s)
     }
 
+    /// Returns the task registry's record of this task.
+    ///
+    /// Unlike other accessors, this doesn't initialize the inner representation.
+    pub(crate) fn record(&self) -> Option<&Arc<Record>> {
+        let raw = self.inner.load(Ordering::Acquire);
+        unsafe { raw.as_ref() }.and_then(|inner| inner.record.as_ref())
+    }
+
     /// Returns the map holding task-local values.
     pub(crate) fn locals(&self) -> &LocalsMap {
         &self.inner().locals
@@ -107,7 +120,7 @@ impl Task {
                 return unsafe { &*raw };
             }
 
-            let new = Arc::into_raw(Arc::new(Inner::new(None))) as *mut Inner;
+            let new = Arc::into_raw(Arc::new(Inner::new(None, None))) as *mut Inner;
             if self.inner.compare_and_swap(raw, new, Ordering::AcqRel) != raw {
                 unsafe {
                     drop(Arc::from_raw(new));
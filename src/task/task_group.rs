@@ -0,0 +1,400 @@
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::future;
+use crate::io;
+use crate::task::{Builder, JoinError, JoinHandle, Poll, Waker};
+
+/// A group of tasks that are cancelled together.
+///
+/// Tasks spawned into a group are its children. The group makes sure they don't outlive it:
+///
+/// * Dropping the group cancels every child that hasn't completed yet.
+/// * If a child panics or gets cancelled, the remaining children are cancelled right away, and
+///   [`join`] returns the error.
+/// * [`join`] waits for all children and collects their outputs. For children returning a
+///   `Result`, [`try_join`] also cancels the remaining children as soon as one returns an error.
+/// * The futures returned by [`join`] and [`cancel`] resolve only after every child has finished,
+///   meaning its future has been dropped.
+///
+/// Children are regular tasks spawned with [`Builder::spawn`], so the `spawn` log event of a
+/// child records the task that spawned it as its `parent_task_id`.
+///
+/// [`join`]: #method.join
+/// [`cancel`]: #method.cancel
+/// [`try_join`]: #method.try_join
+/// [`Builder::spawn`]: struct.Builder.html#method.spawn
+///
+/// # Examples
+///
+/// ```
+/// # async_std::task::block_on(async {
+/// #
+/// use async_std::task::TaskGroup;
+///
+/// let mut group = TaskGroup::new();
+/// for i in 0..3 {
+///     group.spawn(async move { i * 2 });
+/// }
+///
+/// assert_eq!(group.join().await.unwrap(), vec![0, 2, 4]);
+/// #
+/// # })
+/// ```
+pub struct TaskGroup<T> {
+    /// State shared with the children.
+    shared: Arc<Shared<T>>,
+}
+
+/// State shared between a group and its children.
+struct Shared<T> {
+    /// Handles to the children, in the order they were spawned.
+    children: Mutex<Vec<JoinHandle<T>>>,
+
+    /// The number of children whose futures haven't been dropped yet.
+    live: AtomicUsize,
+
+    /// The index of the first child that panicked or got cancelled, or `NO_FAILURE`.
+    failure: AtomicUsize,
+
+    /// Woken up when a child finishes.
+    waker: Mutex<Option<Waker>>,
+}
+
+/// The value of `Shared::failure` while no child has failed.
+const NO_FAILURE: usize = usize::MAX;
+
+/// Marks a child as finished when dropped along with its future.
+///
+/// If the future is dropped before completing, the child has panicked or got cancelled, so its
+/// siblings are cancelled too.
+struct Guard<T> {
+    shared: Arc<Shared<T>>,
+    index: usize,
+    completed: bool,
+}
+
+impl<T> Drop for Guard<T> {
+    fn drop(&mut self) {
+        let shared = &self.shared;
+
+        // Only the first failed child cancels the others. The siblings it cancels fail too, but
+        // they don't lock the handles again, which would deadlock if they are dropped in place.
+        if !self.completed
+            && shared
+                .failure
+                .compare_exchange(NO_FAILURE, self.index, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        {
+            for (i, handle) in shared.children.lock().unwrap().iter().enumerate() {
+                if i != self.index {
+                    handle.abort();
+                }
+            }
+        }
+
+        if shared.live.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(w) = shared.waker.lock().unwrap().take() {
+                w.wake();
+            }
+        }
+    }
+}
+
+impl<T: Send + 'static> TaskGroup<T> {
+    /// Creates an empty group.
+    pub fn new() -> TaskGroup<T> {
+        TaskGroup {
+            shared: Arc::new(Shared {
+                children: Mutex::new(Vec::new()),
+                live: AtomicUsize::new(0),
+                failure: AtomicUsize::new(NO_FAILURE),
+                waker: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Spawns a child task into the group.
+    ///
+    /// To configure the child, use [`Builder::spawn_in`] instead.
+    ///
+    /// [`Builder::spawn_in`]: struct.Builder.html#method.spawn_in
+    #[track_caller]
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        Builder::new()
+            .spawn_in(self, future)
+            .expect("cannot spawn task")
+    }
+
+    /// Spawns a child task with the settings of the given builder.
+    #[track_caller]
+    pub(crate) fn spawn_with<F>(&mut self, builder: Builder, future: F) -> io::Result<()>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let index = self.len();
+        let guard = Guard {
+            shared: self.shared.clone(),
+            index,
+            completed: false,
+        };
+        self.shared.live.fetch_add(1, Ordering::SeqCst);
+
+        let handle = builder.spawn(async move {
+            let mut guard = guard;
+            let output = future.await;
+            guard.completed = true;
+            output
+        })?;
+
+        // If a sibling has already failed, it couldn't cancel this child.
+        let mut children = self.shared.children.lock().unwrap();
+        let failure = self.shared.failure.load(Ordering::SeqCst);
+        if failure != NO_FAILURE && failure != index {
+            handle.abort();
+        }
+        children.push(handle);
+        Ok(())
+    }
+
+    /// Returns the number of children spawned into the group.
+    pub fn len(&self) -> usize {
+        self.shared.children.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no children have been spawned into the group.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits for all children and returns their outputs in the order they were spawned.
+    ///
+    /// If a child panics or gets cancelled, the other children are cancelled and the error of the
+    /// first failed child is returned once all of them have finished.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::task::{self, TaskGroup};
+    ///
+    /// let mut group = TaskGroup::new();
+    /// group.spawn(async { task::sleep(Duration::from_secs(10)).await });
+    /// group.spawn(async { panic!("boom") });
+    ///
+    /// // The sleeping child is cancelled as soon as the other one panics.
+    /// let err = group.join().await.unwrap_err();
+    /// assert!(err.is_panic());
+    /// #
+    /// # })
+    /// ```
+    pub async fn join(self) -> Result<Vec<T>, JoinError> {
+        future::poll_fn(|cx| self.shared.poll_finished(cx.waker())).await;
+
+        let mut children = mem::take(&mut *self.shared.children.lock().unwrap());
+
+        // Return the error of the first failed child rather than that of a cancelled sibling.
+        let failure = self.shared.failure.load(Ordering::SeqCst);
+        if let Some(handle) = children.get_mut(failure) {
+            future::poll_fn(|cx| handle.poll_join(cx)).await?;
+        }
+
+        let mut outputs = Vec::with_capacity(children.len());
+        for handle in &mut children {
+            outputs.push(future::poll_fn(|cx| handle.poll_join(cx)).await?);
+        }
+        Ok(outputs)
+    }
+
+    /// Cancels all children and waits for them to finish.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use async_std::future;
+    /// use async_std::task::TaskGroup;
+    ///
+    /// let mut group = TaskGroup::new();
+    /// group.spawn(future::pending::<()>());
+    /// group.cancel().await;
+    /// #
+    /// # })
+    /// ```
+    pub async fn cancel(self) {
+        self.shared.abort_all();
+        future::poll_fn(|cx| self.shared.poll_finished(cx.waker())).await
+    }
+}
+
+impl<T: Send + 'static, E: Send + 'static> TaskGroup<Result<T, E>> {
+    /// Waits for all children and returns their outputs, unless one of them returns an error.
+    ///
+    /// As soon as a child returns an error, the other children are cancelled and the error is
+    /// returned once all of them have finished. Errors are only noticed while this method is being
+    /// awaited, so children returning an error don't cancel their siblings on their own.
+    ///
+    /// If a child panics or gets cancelled first, that error is returned instead, like in
+    /// [`join`].
+    ///
+    /// [`join`]: #method.join
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use async_std::task::{self, TaskGroup};
+    ///
+    /// let mut group = TaskGroup::new();
+    /// group.spawn(async {
+    ///     task::sleep(Duration::from_secs(10)).await;
+    ///     Ok(1)
+    /// });
+    /// group.spawn(async { Err("boom") });
+    ///
+    /// // The sleeping child is cancelled as soon as the other one returns an error.
+    /// assert_eq!(group.try_join().await.unwrap(), Err("boom"));
+    /// #
+    /// # })
+    /// ```
+    pub async fn try_join(self) -> Result<Result<Vec<T>, E>, JoinError> {
+        let shared = &self.shared;
+        let mut results = Vec::new();
+
+        // Collect the outputs of the children as they complete, until one of them fails.
+        future::poll_fn(|cx| {
+            let mut children = shared.children.lock().unwrap();
+            results.resize_with(children.len(), || None);
+
+            let mut pending = false;
+            for (index, (handle, result)) in children.iter_mut().zip(&mut results).enumerate() {
+                if result.is_some() {
+                    continue;
+                }
+
+                match handle.poll_join(cx) {
+                    Poll::Ready(Ok(Ok(output))) => *result = Some(Ok(Ok(output))),
+                    Poll::Ready(Ok(Err(err))) => {
+                        *result = Some(Ok(Err(err)));
+
+                        // Cancel the siblings, unless one of them has already failed and done so.
+                        // Marking this child as the failed one keeps the cancelled siblings from
+                        // locking the handles again.
+                        if shared
+                            .failure
+                            .compare_exchange(NO_FAILURE, index, Ordering::SeqCst, Ordering::SeqCst)
+                            .is_ok()
+                        {
+                            for (i, handle) in children.iter().enumerate() {
+                                if i != index {
+                                    handle.abort();
+                                }
+                            }
+                        }
+                        return Poll::Ready(());
+                    }
+                    // The failed child has already cancelled its siblings.
+                    Poll::Ready(Err(err)) => {
+                        *result = Some(Err(err));
+                        return Poll::Ready(());
+                    }
+                    Poll::Pending => pending = true,
+                }
+            }
+
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await;
+
+        future::poll_fn(|cx| shared.poll_finished(cx.waker())).await;
+
+        let mut children = mem::take(&mut *shared.children.lock().unwrap());
+        results.resize_with(children.len(), || None);
+
+        // Return the error of the first failed child rather than that of a cancelled sibling.
+        let failure = shared.failure.load(Ordering::SeqCst);
+        if let Some(handle) = children.get_mut(failure) {
+            let result = match results[failure].take() {
+                Some(result) => result,
+                None => future::poll_fn(|cx| handle.poll_join(cx)).await,
+            };
+            if let Err(err) = result? {
+                return Ok(Err(err));
+            }
+        }
+
+        let mut outputs = Vec::with_capacity(children.len());
+        for (handle, result) in children.iter_mut().zip(results) {
+            let result = match result {
+                Some(result) => result,
+                None => future::poll_fn(|cx| handle.poll_join(cx)).await,
+            };
+            match result? {
+                Ok(output) => outputs.push(output),
+                Err(err) => return Ok(Err(err)),
+            }
+        }
+        Ok(Ok(outputs))
+    }
+}
+
+impl<T> Shared<T> {
+    /// Cancels all children.
+    fn abort_all(&self) {
+        // Take the handles out so that the lock isn't held while cancelled children are dropped.
+        let children = mem::take(&mut *self.children.lock().unwrap());
+        for handle in &children {
+            handle.abort();
+        }
+    }
+
+    /// Returns `Poll::Ready` once all children have finished.
+    fn poll_finished(&self, waker: &Waker) -> Poll<()> {
+        // Register the waker first so that a child finishing concurrently is not missed.
+        *self.waker.lock().unwrap() = Some(waker.clone());
+
+        if self.live.load(Ordering::SeqCst) == 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: Send + 'static> Default for TaskGroup<T> {
+    fn default() -> TaskGroup<T> {
+        TaskGroup::new()
+    }
+}
+
+impl<T> Drop for TaskGroup<T> {
+    fn drop(&mut self) {
+        self.shared.abort_all();
+    }
+}
+
+impl<T> fmt::Debug for TaskGroup<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskGroup")
+            .field("children", &self.shared.children.lock().unwrap().len())
+            .field("live", &self.shared.live.load(Ordering::SeqCst))
+            .finish()
+    }
+}
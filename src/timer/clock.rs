@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::task::{Context, Poll, Waker};
+
+thread_local! {
+    /// The virtual clock installed on the current thread.
+    static CURRENT: RefCell<Option<Arc<Clock>>> = RefCell::new(None);
+}
+
+/// A virtual clock.
+#[derive(Debug)]
+pub(crate) struct Clock {
+    /// The point in time the clock started at.
+    start: Instant,
+
+    /// The mutable state of the clock.
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// How far the clock has advanced since it started.
+    elapsed: Duration,
+
+    /// Wakers of pending timers, ordered by their deadlines and then by creation order.
+    timers: BTreeMap<(Instant, u64), Waker>,
+
+    /// The sequence number of the next timer registration.
+    next_id: u64,
+}
+
+#[cfg_attr(not(feature = "default"), allow(dead_code))]
+impl Clock {
+    /// Creates a virtual clock starting at the current system time.
+    pub fn new() -> Arc<Clock> {
+        Arc::new(Clock {
+            start: Instant::now(),
+            state: Mutex::new(State {
+                elapsed: Duration::from_secs(0),
+                timers: BTreeMap::new(),
+                next_id: 0,
+            }),
+        })
+    }
+
+    /// Returns the clock installed on the current thread.
+    pub fn current() -> Option<Arc<Clock>> {
+        CURRENT.try_with(|c| c.borrow().clone()).ok().flatten()
+    }
+
+    /// Installs this clock on the current thread while running a function.
+    pub fn enter<T>(self: &Arc<Clock>, f: impl FnOnce() -> T) -> T {
+        struct Reset(Option<Arc<Clock>>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _reset = Reset(CURRENT.with(|c| c.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// Returns the current virtual time.
+    pub fn now(&self) -> Instant {
+        self.start + self.state.lock().unwrap().elapsed
+    }
+
+    /// Advances the clock to the earliest deadline of all pending timers and wakes them up.
+    ///
+    /// Returns `false` if there are no pending timers.
+    pub fn advance(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        let deadline = match state.timers.keys().next() {
+            Some(&(deadline, _)) => deadline,
+            None => return false,
+        };
+        let now = self.start + state.elapsed;
+        if deadline > now {
+            state.elapsed = deadline - self.start;
+        }
+
+        // Wake up every timer with that deadline, in the order they were registered.
+        let mut wakers = Vec::new();
+        while let Some(&key) = state.timers.keys().next() {
+            if key.0 > deadline {
+                break;
+            }
+            wakers.push(state.timers.remove(&key).unwrap());
+        }
+        drop(state);
+
+        for waker in wakers {
+            waker.wake();
+        }
+        true
+    }
+
+    /// Wakes up all pending timers without advancing the clock.
+    pub fn wake_all(&self) {
+        let timers = std::mem::take(&mut self.state.lock().unwrap().timers);
+        for (_, waker) in timers {
+            waker.wake();
+        }
+    }
+}
+
+/// A timer registered in a virtual clock.
+pub(crate) struct Entry {
+    clock: Arc<Clock>,
+    deadline: Instant,
+
+    /// The key of the registered waker, if any.
+    key: Option<(Instant, u64)>,
+}
+
+impl Entry {
+    /// Creates a timer that fires once the clock reaches the deadline.
+    pub fn new(clock: Arc<Clock>, deadline: Instant) -> Entry {
+        Entry {
+            clock,
+            deadline,
+            key: None,
+        }
+    }
+
+    /// Returns the deadline of the timer.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Changes the deadline of the timer.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deregister();
+        self.deadline = deadline;
+    }
+
+    /// Completes once the clock reaches the deadline.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.clock.state.lock().unwrap();
+
+        if self.clock.start + state.elapsed >= self.deadline {
+            if let Some(key) = self.key.take() {
+                state.timers.remove(&key);
+            }
+            return Poll::Ready(());
+        }
+
+        let key = match self.key {
+            Some(key) => key,
+            None => {
+                let key = (self.deadline, state.next_id);
+                state.next_id += 1;
+                self.key = Some(key);
+                key
+            }
+        };
+        state.timers.insert(key, cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Removes the registered waker from the clock.
+    fn deregister(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.clock.state.lock().unwrap().timers.remove(&key);
+        }
+    }
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        self.deregister();
+    }
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
@@ -0,0 +1,120 @@
+//! Timers backing `task::sleep`, `future::timeout`, `io::timeout`, and the time-based streams.
+//!
+//! A timer follows the system clock through a timing wheel driven by the runtime, unless it is
+//! created on a thread with a virtual clock installed by a simulation. Virtual clocks only move
+//! forward when the simulation advances them, which makes timers fire instantly and in a
+//! reproducible order.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use crate::task::{Context, Poll};
+
+#[cfg(feature = "unstable")]
+pub(crate) use clock::Clock;
+#[cfg(feature = "default")]
+pub(crate) use wheel::process;
+
+#[cfg(feature = "unstable")]
+mod clock;
+mod wheel;
+
+/// A future that completes at a point in time.
+#[derive(Debug)]
+pub(crate) struct Timer {
+    kind: Kind,
+}
+
+enum Kind {
+    /// A timer following the system clock.
+    Real(wheel::Entry),
+
+    /// A timer following a virtual clock.
+    #[cfg(feature = "unstable")]
+    Virtual(clock::Entry),
+}
+
+impl Timer {
+    /// Creates a timer that fires after the given duration.
+    pub fn new(dur: Duration) -> Timer {
+        // Timers too far in the future to represent never fire.
+        let now = now();
+        let deadline = now
+            .checked_add(dur)
+            .unwrap_or_else(|| now + Duration::from_secs(86400 * 365 * 30));
+
+        Timer::at(deadline)
+    }
+
+    /// Creates a timer that fires at the given point in time.
+    pub fn at(deadline: Instant) -> Timer {
+        #[cfg(feature = "unstable")]
+        {
+            if let Some(clock) = Clock::current() {
+                return Timer {
+                    kind: Kind::Virtual(clock::Entry::new(clock, deadline)),
+                };
+            }
+        }
+
+        Timer {
+            kind: Kind::Real(wheel::Entry::new(deadline)),
+        }
+    }
+
+    /// Returns the point in time the timer fires at.
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+    pub fn deadline(&self) -> Instant {
+        match &self.kind {
+            Kind::Real(entry) => entry.deadline(),
+            #[cfg(feature = "unstable")]
+            Kind::Virtual(entry) => entry.deadline(),
+        }
+    }
+
+    /// Resets the timer to fire at the given point in time.
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+    pub fn reset(&mut self, at: Instant) {
+        match &mut self.kind {
+            Kind::Real(entry) => entry.reset(at),
+            #[cfg(feature = "unstable")]
+            Kind::Virtual(entry) => entry.reset(at),
+        }
+    }
+}
+
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &mut self.kind {
+            Kind::Real(entry) => entry.poll(cx),
+            #[cfg(feature = "unstable")]
+            Kind::Virtual(entry) => entry.poll(cx),
+        }
+    }
+}
+
+impl fmt::Debug for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Real(entry) => entry.fmt(f),
+            #[cfg(feature = "unstable")]
+            Kind::Virtual(entry) => entry.fmt(f),
+        }
+    }
+}
+
+/// Returns the current time, as seen by timers created on the current thread.
+pub(crate) fn now() -> Instant {
+    #[cfg(feature = "unstable")]
+    {
+        if let Some(clock) = Clock::current() {
+            return clock.now();
+        }
+    }
+
+    Instant::now()
+}
@@ -0,0 +1,431 @@
+//! A hierarchical timing wheel following the system clock.
+//!
+//! Time is divided into ticks of one millisecond. The wheel has `LEVELS` levels of `SLOTS` slots
+//! each, where a slot on level `n` spans `SLOTS^n` ticks. A timer is stored in the lowest level
+//! whose slots are coarse enough to reach its deadline, so inserting, resetting, and cancelling a
+//! timer takes constant time no matter how many timers are pending.
+//!
+//! When the wheel advances past a slot, timers in it either fire or, if the slot was on a higher
+//! level, get moved into a lower level closer to their deadlines. All timers expiring in the same
+//! tick fire together, so the driving thread wakes up at most once per tick.
+//!
+//! The wheel is driven by the thread polling the networking driver, which uses the deadline of
+//! the next timer as its poll timeout. Without the networking driver, a dedicated thread drives
+//! the wheel instead.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use slab::Slab;
+
+use crate::task::{Context, Poll, Waker};
+
+/// The number of bits of a tick that index a slot in a level.
+const SLOT_BITS: u32 = 6;
+
+/// The number of slots in a level.
+const SLOTS: usize = 1 << SLOT_BITS;
+
+/// The number of levels in the wheel.
+const LEVELS: usize = 6;
+
+/// The number of ticks covered by all levels of the wheel, about two years.
+///
+/// Timers further away than this are placed in the top level, and keep cycling through it until
+/// their deadlines are within reach.
+const MAX_TICKS: u64 = 1 << (SLOT_BITS * LEVELS as u32);
+
+/// The state of the global timing wheel.
+static WHEEL: Lazy<Mutex<Wheel>> = Lazy::new(|| Mutex::new(Wheel::new(Instant::now())));
+
+/// A timer registered in the global timing wheel.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    deadline: Instant,
+
+    /// The key of the timer in the wheel, if registered.
+    key: Option<usize>,
+}
+
+impl Entry {
+    /// Creates a timer that fires at the deadline.
+    pub fn new(deadline: Instant) -> Entry {
+        Entry {
+            deadline,
+            key: None,
+        }
+    }
+
+    /// Returns the deadline of the timer.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Changes the deadline of the timer.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+
+        if let Some(key) = self.key {
+            let mut wheel = WHEEL.lock().unwrap();
+            let tick = wheel.deadline_tick(deadline);
+            let notify = wheel.reset(key, tick);
+            drop(wheel);
+
+            if notify {
+                driver::notify();
+            }
+        }
+    }
+
+    /// Completes once the deadline is reached.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        // The wheel may lag behind the system clock, so check the deadline first.
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        let mut wheel = WHEEL.lock().unwrap();
+
+        let key = match self.key {
+            Some(key) => key,
+            None => {
+                let tick = wheel.deadline_tick(self.deadline);
+                let (key, notify) = wheel.insert(tick, cx.waker().clone());
+                self.key = Some(key);
+                let fired = wheel.timers[key].fired;
+                drop(wheel);
+
+                if notify {
+                    driver::notify();
+                }
+                return if fired {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                };
+            }
+        };
+
+        let timer = &mut wheel.timers[key];
+        if timer.fired {
+            return Poll::Ready(());
+        }
+
+        // Register the task if it isn't registered already.
+        match &timer.waker {
+            Some(w) if w.will_wake(cx.waker()) => {}
+            _ => timer.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            WHEEL.lock().unwrap().remove(key);
+        }
+    }
+}
+
+/// Fires expired timers.
+///
+/// Returns the number of fired timers, along with how long until the next timer expires or `None`
+/// if no timers are pending.
+pub(crate) fn process() -> (usize, Option<Duration>) {
+    let now = Instant::now();
+    let mut wakers = Vec::new();
+
+    let mut wheel = WHEEL.lock().unwrap();
+    let tick = wheel.tick(now);
+    wheel.advance(tick, &mut wakers);
+
+    let next = wheel.next_expiration().map(|(_, _, tick)| tick);
+    wheel.next_wakeup = next;
+    let timeout = next.map(|tick| wheel.instant(tick).saturating_duration_since(now));
+    drop(wheel);
+
+    let fired = wakers.len();
+    for waker in wakers {
+        waker.wake();
+    }
+    (fired, timeout)
+}
+
+/// A timer stored in the wheel.
+struct Timer {
+    /// The tick at which the timer fires.
+    deadline: u64,
+
+    /// Set to `true` once the timer has fired.
+    fired: bool,
+
+    /// The task waiting on the timer.
+    waker: Option<Waker>,
+
+    /// The level and slot holding the timer, if it hasn't fired.
+    position: Option<(usize, usize)>,
+
+    /// The previous timer in the same slot.
+    prev: Option<usize>,
+
+    /// The next timer in the same slot.
+    next: Option<usize>,
+}
+
+/// A level of the wheel.
+#[derive(Clone, Copy)]
+struct Level {
+    /// A bit mask of slots holding at least one timer.
+    occupied: u64,
+
+    /// The first timer in each slot.
+    heads: [Option<usize>; SLOTS],
+}
+
+/// A hierarchical timing wheel.
+struct Wheel {
+    /// The point in time of tick zero.
+    start: Instant,
+
+    /// The tick the wheel has advanced to.
+    elapsed: u64,
+
+    /// The tick the driving thread will wake up at, or `None` if it sleeps until notified.
+    next_wakeup: Option<u64>,
+
+    /// All registered timers.
+    timers: Slab<Timer>,
+
+    /// The levels of the wheel, from the finest to the coarsest.
+    levels: [Level; LEVELS],
+}
+
+impl Wheel {
+    /// Creates an empty wheel starting at the given point in time.
+    fn new(start: Instant) -> Wheel {
+        Wheel {
+            start,
+            elapsed: 0,
+            next_wakeup: None,
+            timers: Slab::new(),
+            levels: [Level {
+                occupied: 0,
+                heads: [None; SLOTS],
+            }; LEVELS],
+        }
+    }
+
+    /// Returns the tick a point in time falls into.
+    fn tick(&self, at: Instant) -> u64 {
+        let dur = at.saturating_duration_since(self.start);
+        dur.as_millis() as u64
+    }
+
+    /// Returns the first tick at which a deadline has passed.
+    fn deadline_tick(&self, deadline: Instant) -> u64 {
+        let dur = deadline.saturating_duration_since(self.start);
+        ((dur.as_nanos() + 999_999) / 1_000_000) as u64
+    }
+
+    /// Returns the point in time a tick starts at.
+    fn instant(&self, tick: u64) -> Instant {
+        self.start + Duration::from_millis(tick)
+    }
+
+    /// Inserts a timer firing at the given tick.
+    ///
+    /// Returns the key of the timer, and whether the driving thread needs to be notified.
+    fn insert(&mut self, deadline: u64, waker: Waker) -> (usize, bool) {
+        let key = self.timers.insert(Timer {
+            deadline,
+            fired: false,
+            waker: Some(waker),
+            position: None,
+            prev: None,
+            next: None,
+        });
+        let notify = self.schedule(key);
+        (key, notify)
+    }
+
+    /// Changes the tick at which a timer fires.
+    ///
+    /// Returns whether the driving thread needs to be notified.
+    fn reset(&mut self, key: usize, deadline: u64) -> bool {
+        self.unlink(key);
+        let timer = &mut self.timers[key];
+        timer.deadline = deadline;
+        timer.fired = false;
+        self.schedule(key)
+    }
+
+    /// Removes a timer.
+    fn remove(&mut self, key: usize) {
+        self.unlink(key);
+        self.timers.remove(key);
+    }
+
+    /// Places a timer into the slot matching its deadline, or fires it if the deadline has passed.
+    ///
+    /// Returns whether the driving thread needs to be notified.
+    fn schedule(&mut self, key: usize) -> bool {
+        let deadline = self.timers[key].deadline;
+        if deadline <= self.elapsed {
+            // The timer is polled right after being inserted or reset, so there's no need to wake
+            // up its task.
+            self.timers[key].fired = true;
+            return false;
+        }
+
+        // The level is determined by the highest bit in which the deadline differs from the
+        // current tick.
+        let masked = (self.elapsed ^ deadline) | (SLOTS as u64 - 1);
+        let masked = masked.min(MAX_TICKS - 1);
+        let level = (63 - masked.leading_zeros()) / SLOT_BITS;
+        let level = level as usize;
+        let slot = (deadline >> (level as u32 * SLOT_BITS)) as usize % SLOTS;
+        self.link(key, level, slot);
+
+        // Wake up the driving thread if it would sleep past the deadline.
+        match self.next_wakeup {
+            Some(tick) if tick <= deadline => false,
+            _ => {
+                self.next_wakeup = Some(deadline);
+                true
+            }
+        }
+    }
+
+    /// Pushes a timer to the front of a slot.
+    fn link(&mut self, key: usize, level: usize, slot: usize) {
+        let head = self.levels[level].heads[slot].replace(key);
+        self.levels[level].occupied |= 1 << slot;
+
+        if let Some(head) = head {
+            self.timers[head].prev = Some(key);
+        }
+        let timer = &mut self.timers[key];
+        timer.position = Some((level, slot));
+        timer.prev = None;
+        timer.next = head;
+    }
+
+    /// Removes a timer from its slot, if it is in one.
+    fn unlink(&mut self, key: usize) {
+        let timer = &mut self.timers[key];
+        let (level, slot) = match timer.position.take() {
+            Some(position) => position,
+            None => return,
+        };
+        let prev = timer.prev.take();
+        let next = timer.next.take();
+
+        match prev {
+            Some(prev) => self.timers[prev].next = next,
+            None => self.levels[level].heads[slot] = next,
+        }
+        if let Some(next) = next {
+            self.timers[next].prev = prev;
+        }
+        if self.levels[level].heads[slot].is_none() {
+            self.levels[level].occupied &= !(1 << slot);
+        }
+    }
+
+    /// Returns the level, slot, and starting tick of the next slot to expire.
+    fn next_expiration(&self) -> Option<(usize, usize, u64)> {
+        // Slots on lower levels always expire before slots on higher levels.
+        self.levels.iter().enumerate().find_map(|(level, l)| {
+            if l.occupied == 0 {
+                return None;
+            }
+
+            let slot_range = 1u64 << (level as u32 * SLOT_BITS);
+            let level_range = slot_range << SLOT_BITS;
+
+            // Find the first occupied slot at or after the current one.
+            let now_slot = (self.elapsed / slot_range) % SLOTS as u64;
+            let zeros = l.occupied.rotate_right(now_slot as u32).trailing_zeros() as u64;
+            let slot = (now_slot + zeros) % SLOTS as u64;
+
+            let level_start = self.elapsed & !(level_range - 1);
+            let mut tick = level_start + slot * slot_range;
+            if tick <= self.elapsed {
+                // Only the top level wraps around, holding timers beyond its range.
+                tick += level_range;
+            }
+            Some((level, slot as usize, tick))
+        })
+    }
+
+    /// Advances the wheel to the given tick, collecting the wakers of fired timers.
+    fn advance(&mut self, now: u64, wakers: &mut Vec<Waker>) {
+        while let Some((level, slot, tick)) = self.next_expiration() {
+            if tick > now {
+                break;
+            }
+            self.elapsed = tick;
+
+            // Take all timers out of the slot, and fire them or move them to a lower level.
+            let mut next = self.levels[level].heads[slot].take();
+            self.levels[level].occupied &= !(1 << slot);
+
+            while let Some(key) = next {
+                let timer = &mut self.timers[key];
+                next = timer.next.take();
+                timer.prev = None;
+                timer.position = None;
+
+                if timer.deadline <= self.elapsed {
+                    timer.fired = true;
+                    wakers.extend(timer.waker.take());
+                } else {
+                    self.schedule(key);
+                }
+            }
+        }
+
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+    }
+}
+
+/// The thread driving the timing wheel.
+mod driver {
+    /// Wakes up the thread polling the networking driver so that it picks a new poll timeout.
+    #[cfg(feature = "default")]
+    pub fn notify() {
+        crate::net::driver::notify();
+    }
+
+    /// Wakes up the dedicated timer thread so that it picks a new timeout.
+    #[cfg(not(feature = "default"))]
+    pub fn notify() {
+        THREAD.unpark();
+    }
+
+    /// The dedicated thread driving timers when the networking driver isn't available.
+    #[cfg(not(feature = "default"))]
+    static THREAD: once_cell::sync::Lazy<std::thread::Thread> = once_cell::sync::Lazy::new(|| {
+        std::thread::Builder::new()
+            .name("async-std/timer".to_string())
+            .spawn(run)
+            .expect("cannot start a thread driving timers")
+            .thread()
+            .clone()
+    });
+
+    /// Processes timers on the dedicated thread, parking it until the next deadline.
+    #[cfg(not(feature = "default"))]
+    fn run() {
+        loop {
+            match super::process().1 {
+                Some(timeout) => std::thread::park_timeout(timeout),
+                None => std::thread::park(),
+            }
+        }
+    }
+}
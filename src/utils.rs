@@ -20,22 +20,32 @@ pub fn abort_on_panic<T>(f: impl FnOnce() -> T) -> T {
     t
 }
 
-/// Generates a random number in `0..n`.
 #[cfg(any(feature = "unstable", feature = "default"))]
-pub fn random(n: u32) -> u32 {
-    use std::cell::Cell;
-    use std::num::Wrapping;
-
-    thread_local! {
-        static RNG: Cell<Wrapping<u32>> = {
-            // Take the address of a local value as seed.
-            let mut x = 0i32;
-            let r = &mut x;
-            let addr = r as *mut i32 as usize;
-            Cell::new(Wrapping(addr as u32))
-        }
+thread_local! {
+    /// The state of the random number generator of the current thread.
+    static RNG: std::cell::Cell<std::num::Wrapping<u32>> = {
+        // Take the address of a local value as seed.
+        let mut x = 0i32;
+        let r = &mut x;
+        let addr = r as *mut i32 as usize;
+        std::cell::Cell::new(std::num::Wrapping(addr as u32))
     }
+}
+
+/// Seeds the random number generator of the current thread.
+///
+/// This makes the numbers generated by `random` on this thread reproducible.
+#[cfg(all(feature = "unstable", feature = "default"))]
+pub fn seed_random(seed: u64) {
+    // Xorshift gets stuck at zero, so fold the seed into a non-zero state.
+    let x = (seed ^ (seed >> 32)) as u32;
+    let x = if x == 0 { 0x9e37_79b9 } else { x };
+    RNG.with(|rng| rng.set(std::num::Wrapping(x)));
+}
 
+/// Generates a random number in `0..n`.
+#[cfg(any(feature = "unstable", feature = "default"))]
+pub fn random(n: u32) -> u32 {
     RNG.with(|rng| {
         // This is the 32-bit variant of Xorshift.
         //
@@ -0,0 +1,165 @@
+#![cfg(unix)]
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use async_std::future;
+use async_std::os::unix::io::{Async, Interest, Mode};
+use async_std::prelude::*;
+use async_std::task;
+
+fn pair() -> std::io::Result<(Async<UnixStream>, Async<UnixStream>)> {
+    let (a, b) = UnixStream::pair()?;
+    a.set_nonblocking(true)?;
+    b.set_nonblocking(true)?;
+    Ok((Async::new(a)?, Async::new(b)?))
+}
+
+#[test]
+fn read_with_waits_for_data() -> std::io::Result<()> {
+    task::block_on(async {
+        let (a, b) = pair()?;
+
+        let reader = task::spawn(async move {
+            let mut buf = [0u8; 5];
+            let n = a.read_with(|mut a| a.read(&mut buf)).await?;
+            Ok::<_, std::io::Error>(buf[..n].to_vec())
+        });
+
+        task::sleep(Duration::from_millis(10)).await;
+        b.writable().await?;
+        b.write_with(|mut b| b.write(b"hello")).await?;
+
+        assert_eq!(reader.await?, b"hello");
+        Ok(())
+    })
+}
+
+#[test]
+fn readable_completes_when_data_arrives() -> std::io::Result<()> {
+    task::block_on(async {
+        let (mut a, mut b) = pair()?;
+
+        // Nothing has been written yet.
+        let res = future::timeout(Duration::from_millis(10), a.readable()).await;
+        assert!(res.is_err());
+
+        b.write_all(b"ping").await?;
+        a.readable().await?;
+
+        let mut buf = Vec::new();
+        drop(b);
+        a.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"ping");
+        Ok(())
+    })
+}
+
+#[test]
+fn clear_ready_waits_for_next_event() -> std::io::Result<()> {
+    task::block_on(async {
+        let (a, b) = pair()?;
+        b.get_ref().write_all(b"ping")?;
+
+        // Drain the socket until it would block.
+        let mut buf = [0u8; 16];
+        loop {
+            let guard = a.readable().await?;
+            match guard.get_ref().read(&mut buf) {
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    guard.clear_ready();
+                    break;
+                }
+                res => assert_eq!(res?, 4),
+            }
+        }
+
+        // The readiness has been cleared, so waiting blocks until more data arrives.
+        let res = future::timeout(Duration::from_millis(10), a.readable()).await;
+        assert!(res.is_err());
+
+        b.get_ref().write_all(b"pong")?;
+        a.readable().await?;
+        Ok(())
+    })
+}
+
+#[test]
+fn into_inner_returns_handle() -> std::io::Result<()> {
+    let (a, _b) = pair()?;
+    let a: UnixStream = a.into_inner()?;
+    assert!(a.peer_addr().is_ok());
+    Ok(())
+}
+
+#[test]
+fn interest_limits_readiness() -> std::io::Result<()> {
+    task::block_on(async {
+        let (a, b) = UnixStream::pair()?;
+        a.set_nonblocking(true)?;
+        let a = Async::with_interest(a, Interest::WRITABLE, Mode::Edge)?;
+
+        (&b).write_all(b"ping")?;
+        a.writable().await?;
+
+        // Readability was not part of the interest.
+        let res = future::timeout(Duration::from_millis(10), a.readable()).await;
+        assert!(res.is_err());
+        Ok(())
+    })
+}
+
+#[test]
+fn level_mode_rearms_while_waiting() -> std::io::Result<()> {
+    task::block_on(async {
+        let (a, b) = UnixStream::pair()?;
+        a.set_nonblocking(true)?;
+        let a = Async::with_interest(a, Interest::READABLE, Mode::Level)?;
+
+        for _ in 0..3 {
+            let mut buf = [0u8; 4];
+            let read = a.read_with(|mut a| a.read(&mut buf));
+            let write = async {
+                task::sleep(Duration::from_millis(5)).await;
+                (&b).write_all(b"ping")
+            };
+            let (n, res) = future::timeout(Duration::from_secs(1), read.join(write))
+                .await
+                .expect("reader was not woken up");
+            res?;
+            assert_eq!(&buf[..n?], b"ping");
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn oneshot_mode_requires_rearm() -> std::io::Result<()> {
+    task::block_on(async {
+        let (a, b) = UnixStream::pair()?;
+        a.set_nonblocking(true)?;
+        let a = Async::with_interest(a, Interest::READABLE, Mode::Oneshot)?;
+
+        (&b).write_all(b"ping")?;
+        a.readable().await?;
+        let mut buf = [0u8; 4];
+        a.read_with(|mut a| a.read(&mut buf)).await?;
+
+        // Nothing is left to read, which clears the readiness.
+        let res = future::timeout(
+            Duration::from_millis(10),
+            a.read_with(|mut a| a.read(&mut buf)),
+        );
+        assert!(res.await.is_err());
+
+        // The registration is disarmed until it is re-armed.
+        (&b).write_all(b"pong")?;
+        let res = future::timeout(Duration::from_millis(10), a.readable()).await;
+        assert!(res.is_err());
+
+        a.rearm()?;
+        a.readable().await?;
+        Ok(())
+    })
+}
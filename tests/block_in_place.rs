@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn other_tasks_keep_running() {
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+    static STOPPED: AtomicUsize = AtomicUsize::new(0);
+
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .on_thread_start(|| {
+            STARTED.fetch_add(1, Ordering::SeqCst);
+        })
+        .on_thread_stop(|| {
+            STOPPED.fetch_add(1, Ordering::SeqCst);
+        })
+        .build_executor()
+        .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let blocked = executor.spawn(async move {
+        let mut seen = Vec::new();
+        task::block_in_place(|| {
+            // Wait for a task spawned onto the same single-threaded executor.
+            seen.push(receiver.recv_timeout(Duration::from_secs(5)).unwrap());
+        });
+        seen
+    });
+
+    thread::sleep(Duration::from_millis(10));
+    executor.spawn(async move { sender.send(1).unwrap() });
+
+    assert_eq!(task::block_on(blocked), vec![1]);
+
+    // The blocked worker thread was replaced by a new one, and exits once its task is done.
+    for _ in 0..100 {
+        if STOPPED.load(Ordering::SeqCst) == 1 {
+            assert_eq!(STARTED.load(Ordering::SeqCst), 2);
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("the blocked worker thread did not exit");
+}
+
+#[test]
+fn outside_worker_thread() {
+    let mut x = 0;
+    task::block_in_place(|| x += 1);
+    assert_eq!(x, 1);
+
+    task::block_on(async {
+        task::block_in_place(|| x += 1);
+    });
+    assert_eq!(x, 2);
+}
+
+#[test]
+fn nested() {
+    let res = task::block_on(task::spawn(async {
+        task::block_in_place(|| task::block_in_place(|| 1 + 2))
+    }));
+    assert_eq!(res, 3);
+}
@@ -0,0 +1,64 @@
+use std::sync::mpsc;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use async_std::io;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn bounded_blocking_pool() {
+    RuntimeBuilder::new()
+        .max_blocking_threads(2)
+        .blocking_keep_alive(Duration::from_millis(100))
+        .blocking_thread_name("custom-blocking".to_string())
+        .blocking_queue_capacity(1)
+        .build()
+        .unwrap();
+
+    // Occupy both threads.
+    let barrier = Arc::new(Barrier::new(3));
+    let (sender, receiver) = mpsc::channel();
+    let busy: Vec<_> = (0..2)
+        .map(|_| {
+            let barrier = barrier.clone();
+            let sender = sender.clone();
+            task::spawn_blocking(move || {
+                sender
+                    .send(thread::current().name().map(String::from))
+                    .unwrap();
+                barrier.wait();
+            })
+        })
+        .collect();
+    for _ in 0..2 {
+        assert_eq!(receiver.recv().unwrap().as_deref(), Some("custom-blocking"));
+    }
+
+    // The third task has to wait in the queue.
+    let queued = task::try_spawn_blocking(|| 3).unwrap();
+    let metrics = task::metrics();
+    assert_eq!(metrics.blocking_threads(), 2);
+    assert_eq!(metrics.blocking_queue_depth(), 1);
+
+    // The queue is full.
+    let err = task::try_spawn_blocking(|| ()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+    barrier.wait();
+    task::block_on(async {
+        for handle in busy {
+            handle.await;
+        }
+        assert_eq!(queued.await, 3);
+    });
+
+    // Idle threads stop after the keep-alive timeout.
+    for _ in 0..100 {
+        if task::metrics().blocking_threads() == 0 {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("idle blocking threads did not stop");
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_std::sync::Mutex;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn always_ready_task_yields() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+
+    let spawner = executor.handle();
+    let handle = executor.spawn(async move {
+        let mutex = Mutex::new(());
+        let flag = Arc::new(AtomicBool::new(false));
+
+        // The other task can only run on the single worker thread once this one yields.
+        let f = flag.clone();
+        spawner
+            .spawn(async move { f.store(true, Ordering::SeqCst) })
+            .unwrap();
+
+        let mut locks = 0u64;
+        while !flag.load(Ordering::SeqCst) {
+            drop(mutex.lock().await);
+            locks += 1;
+        }
+        locks
+    });
+
+    assert!(task::block_on(handle) > 0);
+}
+
+#[test]
+fn unconstrained_task_does_not_yield() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+
+    let spawner = executor.handle();
+    let handle = task::Builder::new()
+        .budget(None)
+        .spawn_on(&executor.handle(), async move {
+            let mutex = Mutex::new(());
+            let flag = Arc::new(AtomicBool::new(false));
+
+            let f = flag.clone();
+            spawner
+                .spawn(async move { f.store(true, Ordering::SeqCst) })
+                .unwrap();
+
+            for _ in 0..10_000 {
+                drop(mutex.lock().await);
+            }
+            flag.load(Ordering::SeqCst)
+        })
+        .unwrap();
+
+    assert!(!task::block_on(handle));
+}
@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, RuntimeBuilder};
+
+/// Sets a flag when dropped.
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Waits until the flag is set by the executor dropping a cancelled task.
+async fn wait_for(flag: &AtomicBool) {
+    for _ in 0..100 {
+        if flag.load(Ordering::SeqCst) {
+            return;
+        }
+        task::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the task was not cancelled");
+}
+
+#[test]
+fn cancel_pending() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let handle = task::spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+        });
+        task::sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(handle.cancel().await, None);
+        wait_for(&dropped).await;
+    });
+}
+
+#[test]
+fn cancel_completed() {
+    task::block_on(async {
+        let handle = task::spawn(async { 7 });
+        task::sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(handle.cancel().await, Some(7));
+    });
+}
+
+#[test]
+fn try_join_cancelled() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+
+    // Keep the only worker thread busy so that the next task stays in the queue.
+    executor.spawn(async { thread::sleep(Duration::from_millis(50)) });
+    let handle = executor.spawn(future::pending::<()>());
+
+    // Dropping the executor cancels the queued task.
+    drop(executor);
+
+    let err = task::block_on(handle.try_join()).unwrap_err();
+    assert!(err.is_cancelled());
+}
+
+#[test]
+fn abort_on_drop() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let handle = task::spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+        })
+        .abort_on_drop();
+        drop(handle);
+
+        wait_for(&dropped).await;
+    });
+}
+
+#[test]
+fn abort_on_drop_await() {
+    task::block_on(async {
+        let handle = task::spawn(async { 1 + 2 }).abort_on_drop();
+        assert_eq!(handle.await, 3);
+    });
+}
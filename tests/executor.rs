@@ -0,0 +1,70 @@
+use std::thread;
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn spawn_on_executor() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .thread_name("isolated".to_string())
+        .build_executor()
+        .unwrap();
+    assert_eq!(executor.num_threads(), 2);
+
+    let handle = executor.handle();
+    let name = task::block_on(async move {
+        handle
+            .spawn(async { thread::current().name().map(|s| s.to_string()) })
+            .unwrap()
+            .await
+    });
+    assert_eq!(name.as_deref(), Some("isolated"));
+}
+
+#[test]
+fn spawn_named_task_on_executor() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+
+    let handle = task::Builder::new()
+        .name("child".to_string())
+        .spawn_on(&executor.handle(), async {
+            task::current().name().map(|s| s.to_string())
+        })
+        .unwrap();
+    assert_eq!(handle.task().name(), Some("child"));
+    assert_eq!(task::block_on(handle).as_deref(), Some("child"));
+}
+
+#[test]
+fn spawn_after_drop() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+    let handle = executor.handle();
+    drop(executor);
+
+    assert!(handle.spawn(async {}).is_err());
+}
+
+#[test]
+fn drop_cancels_idle_tasks() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+    let idle = executor.spawn(future::pending::<()>());
+
+    // Give the task a chance to run, so that it is idle rather than queued.
+    thread::sleep(Duration::from_millis(10));
+    drop(executor);
+
+    let res = task::block_on(future::timeout(Duration::from_secs(1), idle.try_join()));
+    let err = res.expect("the idle task was not cancelled").unwrap_err();
+    assert!(err.is_cancelled());
+}
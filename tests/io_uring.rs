@@ -0,0 +1,135 @@
+#![cfg(target_os = "linux")]
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use async_std::fs::{self, File};
+use async_std::future;
+use async_std::io;
+use async_std::net::{TcpListener, TcpStream, UdpSocket};
+use async_std::os::unix::net::UnixStream;
+use async_std::prelude::*;
+use async_std::task::{self, Poll};
+
+#[test]
+fn tcp_large_transfer() -> io::Result<()> {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        // More data than fits into the socket buffers, so that writes wait for the peer.
+        let data: Vec<u8> = (0..4 << 20).map(|i| i as u8).collect();
+        let expected = data.clone();
+
+        let writer = task::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await?;
+            stream.write_all(&data).await?;
+            Ok::<_, io::Error>(())
+        });
+
+        let (mut stream, _) = listener.accept().await?;
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await?;
+
+        writer.await?;
+        assert!(received == expected);
+        Ok(())
+    })
+}
+
+#[test]
+fn tcp_write_abandoned_then_retried_with_other_data() -> io::Result<()> {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let mut stream = TcpStream::connect(listener.local_addr()?).await?;
+        let (mut peer, _) = listener.accept().await?;
+
+        // Abandon a write after polling it once. Writes may complete right away, so keep trying
+        // until one is left in flight.
+        let first = [0xaa; 16];
+        for _ in 0..1000 {
+            let mut write = stream.write(&first);
+            let poll = future::poll_fn(|cx| Poll::Ready(Pin::new(&mut write).poll(cx))).await;
+            if poll.is_pending() {
+                break;
+            }
+        }
+
+        stream.write_all(&[0xbb; 8]).await?;
+        drop(stream);
+
+        // The abandoned write may still go through, but the new data must follow it.
+        let mut received = Vec::new();
+        peer.read_to_end(&mut received).await?;
+        let split = received.len() - 8;
+        assert!(received[..split].iter().all(|&b| b == 0xaa));
+        assert_eq!(&received[split..], &[0xbb; 8]);
+        Ok(())
+    })
+}
+
+#[test]
+fn unix_stream_dropped_while_reading() -> io::Result<()> {
+    task::block_on(async {
+        let (a, mut b) = UnixStream::pair()?;
+
+        // Abandon a read that is still waiting for data.
+        let mut buf = [0u8; 4];
+        let res = future::timeout(Duration::from_millis(10), (&a).read(&mut buf)).await;
+        assert!(res.is_err());
+        drop(a);
+
+        let mut rest = Vec::new();
+        b.read_to_end(&mut rest).await?;
+        assert!(rest.is_empty());
+        Ok(())
+    })
+}
+
+#[test]
+fn udp_send_recv() -> io::Result<()> {
+    task::block_on(async {
+        let a = UdpSocket::bind("127.0.0.1:0").await?;
+        let b = UdpSocket::bind("127.0.0.1:0").await?;
+        a.connect(b.local_addr()?).await?;
+        b.connect(a.local_addr()?).await?;
+
+        a.send(b"hello").await?;
+        a.send(b"world").await?;
+
+        // Datagrams keep their boundaries, and the truncated part is discarded.
+        let mut buf = [0u8; 3];
+        assert_eq!(b.recv(&mut buf).await?, 3);
+        assert_eq!(&buf, b"hel");
+        let mut buf = [0u8; 16];
+        let n = b.recv(&mut buf).await?;
+        assert_eq!(&buf[..n], b"world");
+        Ok(())
+    })
+}
+
+#[test]
+fn file_roundtrip() -> io::Result<()> {
+    let dir = tempdir::TempDir::new("io_uring")?;
+    let path = dir.path().join("file");
+
+    task::block_on(async {
+        let data: Vec<u8> = (0..1 << 20).map(|i| (i % 251) as u8).collect();
+
+        let mut file = File::create(&path).await?;
+        file.write_all(&data).await?;
+        file.write_all(b"tail").await?;
+        file.sync_all().await?;
+        drop(file);
+
+        let mut file = File::open(&path).await?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).await?;
+        assert_eq!(contents.len(), data.len() + 4);
+        assert!(contents[..data.len()] == data[..]);
+        assert_eq!(&contents[data.len()..], b"tail");
+
+        assert_eq!(fs::read(&path).await?, contents);
+        Ok(())
+    })
+}
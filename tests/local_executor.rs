@@ -0,0 +1,90 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, LocalExecutor};
+use async_std::task_local;
+
+#[test]
+fn spawn_local() {
+    let executor = LocalExecutor::new();
+    let counter = Rc::new(Cell::new(0));
+
+    let c = counter.clone();
+    let res = executor.run(async move {
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let c = c.clone();
+                task::spawn_local(async move { c.set(c.get() + 1) })
+            })
+            .collect();
+        for handle in handles {
+            handle.await;
+        }
+        c.get()
+    });
+
+    assert_eq!(res, 10);
+    assert_eq!(counter.get(), 10);
+}
+
+#[test]
+fn woken_by_other_thread() {
+    let executor = LocalExecutor::new();
+    let rc = Rc::new(7);
+
+    let handle = executor.spawn(async move {
+        // The global executor wakes this task up from another thread.
+        task::spawn(task::sleep(Duration::from_millis(10))).await;
+        *rc
+    });
+
+    assert_eq!(executor.run(handle), 7);
+}
+
+#[test]
+fn task_locals() {
+    task_local! {
+        static LOCAL: Cell<u32> = Cell::new(0);
+    }
+
+    LocalExecutor::new().run(async {
+        LOCAL.with(|l| l.set(1));
+
+        let handle = task::spawn_local(async {
+            LOCAL.with(|l| l.set(l.get() + 5));
+            LOCAL.with(|l| l.get())
+        });
+
+        assert_eq!(handle.await, 5);
+        assert_eq!(LOCAL.with(|l| l.get()), 1);
+    });
+}
+
+#[test]
+fn spawn_local_outside_executor() {
+    let res = task::Builder::new().spawn_local(async {});
+    assert!(res.is_err());
+}
+
+#[test]
+fn drop_cancels_idle_tasks() {
+    let executor = LocalExecutor::new();
+    let rc = Rc::new(());
+
+    let r = rc.clone();
+    let idle = executor.spawn(async move {
+        let _r = r;
+        future::pending::<()>().await
+    });
+
+    // Run the task once, so that it is idle rather than queued.
+    executor.run(task::yield_now());
+    drop(executor);
+
+    let res = task::block_on(future::timeout(Duration::from_secs(1), idle.try_join()));
+    let err = res.expect("the idle task was not cancelled").unwrap_err();
+    assert!(err.is_cancelled());
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn executor_counters() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .build_executor()
+        .unwrap();
+
+    let metrics = executor.metrics();
+    assert_eq!(metrics.num_workers(), 2);
+    assert_eq!(metrics.local_queue_depths().len(), 2);
+    assert_eq!(metrics.spawned_tasks(), 0);
+
+    let handles: Vec<_> = (0..10).map(|i| executor.spawn(async move { i })).collect();
+    let pending = executor.spawn(future::pending::<()>());
+    for handle in handles {
+        task::block_on(handle);
+    }
+
+    let metrics = executor.metrics();
+    assert_eq!(metrics.spawned_tasks(), 11);
+    assert_eq!(metrics.completed_tasks(), 10);
+    assert_eq!(metrics.live_tasks(), 1);
+
+    task::block_on(pending.cancel());
+    task::block_on(async {
+        while executor.metrics().live_tasks() > 0 {
+            task::sleep(Duration::from_millis(1)).await;
+        }
+    });
+    assert_eq!(executor.metrics().completed_tasks(), 11);
+}
+
+#[test]
+fn idle_workers_sleep() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(3)
+        .build_executor()
+        .unwrap();
+
+    task::block_on(async {
+        while executor.metrics().sleeping_workers() < 3 {
+            task::sleep(Duration::from_millis(1)).await;
+        }
+    });
+
+    let metrics = executor.metrics();
+    assert_eq!(metrics.global_queue_depth(), 0);
+    assert_eq!(metrics.local_queue_depths(), &[0, 0, 0]);
+}
+
+#[test]
+fn blocking_threads() {
+    task::block_on(async {
+        task::spawn_blocking(|| {
+            let metrics = task::metrics();
+            assert!(metrics.blocking_threads() >= 1);
+            assert!(metrics.blocking_threads() > metrics.idle_blocking_threads());
+        })
+        .await;
+    });
+}
@@ -0,0 +1,67 @@
+use std::sync::{mpsc, Mutex};
+
+use async_std::task;
+
+#[test]
+fn try_join_panicked() {
+    task::block_on(async {
+        let handle = task::spawn(async {
+            panic!("boom");
+        });
+
+        let err = handle.try_join().await.unwrap_err();
+        assert!(err.is_panic());
+        assert_eq!(err.to_string(), "task panicked: boom");
+
+        // The executor keeps running other tasks.
+        assert_eq!(task::spawn(async { 1 + 2 }).await, 3);
+    });
+}
+
+#[test]
+fn try_join_blocking_panicked() {
+    task::block_on(async {
+        let handle = task::spawn_blocking(|| {
+            panic!("blocking boom");
+        });
+
+        let payload = handle.try_join().await.unwrap_err().try_into_panic();
+        assert_eq!(
+            *payload.unwrap().downcast::<&str>().unwrap(),
+            "blocking boom"
+        );
+    });
+}
+
+#[test]
+#[should_panic = "inner boom"]
+fn await_propagates_panic() {
+    task::block_on(async {
+        task::spawn(async {
+            panic!("inner boom");
+        })
+        .await;
+    });
+}
+
+#[test]
+fn panic_hook_for_detached_task() {
+    let (s, r) = mpsc::channel();
+    let s = Mutex::new(s);
+
+    task::set_panic_hook(Box::new(move |task, err| {
+        if task.name() == Some("detached") {
+            s.lock().unwrap().send(err.to_string()).unwrap();
+        }
+    }));
+
+    task::Builder::new()
+        .name("detached".to_string())
+        .spawn(async {
+            panic!("detached boom");
+        })
+        .unwrap();
+
+    assert_eq!(r.recv().unwrap(), "task panicked: detached boom");
+    drop(task::take_panic_hook());
+}
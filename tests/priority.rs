@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+
+use async_std::task::{self, Builder, Priority, RuntimeBuilder};
+
+#[test]
+fn higher_priority_runs_first() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+
+    let spawner = executor.handle();
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let o = order.clone();
+
+    // The spawned tasks can only run on the single worker thread once this one completes.
+    let handle = executor.spawn(async move {
+        let mut handles = Vec::new();
+        for &(priority, name) in &[
+            (Priority::Low, "low"),
+            (Priority::Normal, "normal 1"),
+            (Priority::Normal, "normal 2"),
+            (Priority::High, "high"),
+        ] {
+            let o = o.clone();
+            let handle = Builder::new()
+                .priority(priority)
+                .spawn_on(&spawner, async move { o.lock().unwrap().push(name) })
+                .unwrap();
+            handles.push(handle);
+        }
+        handles
+    });
+
+    for handle in task::block_on(handle) {
+        task::block_on(handle);
+    }
+    let order = order.lock().unwrap();
+    assert_eq!(order.len(), 4);
+    assert_eq!(order[0], "high");
+    assert_eq!(order[3], "low");
+}
+
+#[test]
+fn priority_from_outside_the_executor() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .build_executor()
+        .unwrap();
+
+    let handle = Builder::new()
+        .priority(Priority::Low)
+        .spawn_on(&executor.handle(), async { 1 + 2 })
+        .unwrap();
+    assert_eq!(task::block_on(handle), 3);
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, TaskState};
+
+#[test]
+fn dump_live_tasks() {
+    task::enable_registry();
+
+    task::block_on(async {
+        let parent = task::spawn(async {
+            let builder = task::Builder::new().name("child".to_string());
+            let (child, line) = (builder.spawn(future::pending::<()>()).unwrap(), line!());
+            (task::current().id(), child, line)
+        });
+        let (parent_id, child, line) = parent.await;
+        task::sleep(Duration::from_millis(10)).await;
+
+        let dump = task::dump();
+        let info = dump
+            .tasks()
+            .iter()
+            .find(|t| t.id() == child.task().id())
+            .expect("the child is not in the registry");
+
+        assert_eq!(info.name(), Some("child"));
+        assert_eq!(info.parent_id(), Some(parent_id));
+        assert_eq!(info.state(), TaskState::Idle);
+        assert_eq!(info.location().file(), file!());
+        assert_eq!(info.location().line(), line);
+        assert!(info.age() >= Duration::from_millis(10));
+
+        // The parent has completed, so it is no longer listed.
+        assert!(dump.tasks().iter().all(|t| t.id() != parent_id));
+
+        let printed = dump.to_string();
+        assert!(printed.contains(&format!("task {} \"child\" (idle", info.id())));
+
+        // Cancelled tasks are removed from the registry.
+        let id = child.task().id();
+        child.cancel().await;
+        for _ in 0..100 {
+            if task::dump().tasks().iter().all(|t| t.id() != id) {
+                return;
+            }
+            task::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("the cancelled task is still in the registry");
+    });
+}
+
+#[test]
+fn running_state() {
+    task::enable_registry();
+
+    let handle = task::spawn(async {
+        let id = task::current().id();
+        let dump = task::dump();
+        dump.tasks().iter().find(|t| t.id() == id).unwrap().state()
+    });
+    assert_eq!(task::block_on(handle), TaskState::Running);
+}
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use async_std::io;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn configure_global_runtime() {
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+    let rt = RuntimeBuilder::new()
+        .num_threads(3)
+        .thread_name("custom-executor".to_string())
+        .stack_size(4 * 1024 * 1024)
+        .reactor_shards(2)
+        .on_thread_start(|| {
+            STARTED.fetch_add(1, Ordering::SeqCst);
+        })
+        .build()
+        .unwrap();
+    assert_eq!(rt.num_threads(), 3);
+    assert_eq!(rt.thread_name(), "custom-executor");
+
+    let name = task::block_on(task::spawn(async {
+        thread::current().name().map(|s| s.to_string())
+    }));
+    assert_eq!(name.as_deref(), Some("custom-executor"));
+    assert!(STARTED.load(Ordering::SeqCst) >= 1);
+
+    // The runtime cannot be configured once it has started.
+    let err = RuntimeBuilder::new().num_threads(1).build().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+}
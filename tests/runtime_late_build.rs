@@ -0,0 +1,34 @@
+use std::thread;
+use std::time::Duration;
+
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn spawn_blocking_before_build() {
+    let name = task::block_on(task::spawn_blocking(|| {
+        thread::current().name().map(String::from)
+    }));
+    assert_eq!(name.as_deref(), Some("async-std/blocking"));
+
+    // The blocking pool doesn't lock in the default configuration.
+    let rt = RuntimeBuilder::new()
+        .num_threads(2)
+        .blocking_thread_name("late-blocking".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(rt.num_threads(), 2);
+
+    // Wait for the idle blocking thread to stop, so that the next task starts a new one.
+    for _ in 0..300 {
+        if task::metrics().blocking_threads() == 0 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(task::metrics().blocking_threads(), 0);
+
+    let name = task::block_on(task::spawn_blocking(|| {
+        thread::current().name().map(String::from)
+    }));
+    assert_eq!(name.as_deref(), Some("late-blocking"));
+}
@@ -0,0 +1,32 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use async_std::net::TcpListener;
+use async_std::task::{self, RuntimeBuilder};
+
+#[test]
+fn shutdown_global_runtime() {
+    let rt = RuntimeBuilder::new().num_threads(2).build().unwrap();
+
+    // A task blocked on network I/O is cancelled at the deadline.
+    let listener = task::block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let accepting = rt.spawn(async move { listener.accept().await.is_ok() });
+
+    // A blocking task finishes before the deadline.
+    let finished = Arc::new(AtomicBool::new(false));
+    let f = finished.clone();
+    rt.spawn(async move {
+        task::spawn_blocking(move || {
+            thread::sleep(Duration::from_millis(20));
+            f.store(true, Ordering::SeqCst);
+        })
+        .await
+    });
+
+    rt.shutdown_timeout(Duration::from_millis(200));
+    assert!(finished.load(Ordering::SeqCst));
+    assert_eq!(task::block_on(accepting.cancel()), None);
+    assert!(task::Builder::new().spawn(async {}).is_err());
+}
@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use async_std::future;
+use async_std::task::{self, RuntimeBuilder};
+
+/// Sets a flag when dropped.
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn waits_for_tasks() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .build_executor()
+        .unwrap();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let d = done.clone();
+    executor.spawn(async move {
+        task::sleep(Duration::from_millis(50)).await;
+        d.store(true, Ordering::SeqCst);
+    });
+
+    let start = Instant::now();
+    executor.shutdown_timeout(Duration::from_secs(10));
+    assert!(done.load(Ordering::SeqCst));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn rejects_outside_spawns_while_draining() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .build_executor()
+        .unwrap();
+    let handle = executor.handle();
+    let inner = executor.handle();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let d = done.clone();
+    executor.spawn(async move {
+        task::sleep(Duration::from_millis(100)).await;
+
+        // Tasks running on the executor can still spawn children while it drains.
+        let child = inner.spawn(async move {
+            d.store(true, Ordering::SeqCst);
+        });
+        child.unwrap().await;
+    });
+
+    let shutdown = thread::spawn(move || executor.shutdown_timeout(Duration::from_secs(10)));
+
+    // Spawning from outside the executor fails as soon as it starts shutting down.
+    thread::sleep(Duration::from_millis(30));
+    assert!(handle.spawn(async {}).is_err());
+
+    let start = Instant::now();
+    shutdown.join().unwrap();
+    assert!(done.load(Ordering::SeqCst));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn cancels_tasks_after_deadline() {
+    static STOPPED: AtomicUsize = AtomicUsize::new(0);
+
+    let executor = RuntimeBuilder::new()
+        .num_threads(2)
+        .on_thread_stop(|| {
+            STOPPED.fetch_add(1, Ordering::SeqCst);
+        })
+        .build_executor()
+        .unwrap();
+
+    let idle = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(idle.clone());
+    executor.spawn(async move {
+        let _flag = flag;
+        future::pending::<()>().await
+    });
+
+    let sleeping = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(sleeping.clone());
+    executor.spawn(async move {
+        let _flag = flag;
+        task::sleep(Duration::from_secs(60)).await
+    });
+
+    executor.shutdown_timeout(Duration::from_millis(10));
+    assert!(idle.load(Ordering::SeqCst));
+    assert!(sleeping.load(Ordering::SeqCst));
+    assert_eq!(STOPPED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn rejects_new_tasks() {
+    let executor = RuntimeBuilder::new()
+        .num_threads(1)
+        .build_executor()
+        .unwrap();
+    let handle = executor.handle();
+
+    executor.shutdown_timeout(Duration::from_secs(1));
+    assert!(handle.spawn(async {}).is_err());
+}
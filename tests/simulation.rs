@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_std::prelude::*;
+use async_std::task::{self, Simulation};
+use async_std::{future, io, stream};
+
+#[test]
+fn timers_follow_virtual_clock() {
+    let sim = Simulation::new(1);
+    let start = sim.now();
+    let real_start = Instant::now();
+
+    sim.block_on(async {
+        task::sleep(Duration::from_secs(3600)).await;
+
+        let res = future::timeout(Duration::from_secs(60), future::pending::<()>()).await;
+        assert!(res.is_err());
+
+        let res = io::timeout(Duration::from_secs(60), future::pending::<io::Result<()>>()).await;
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::TimedOut);
+
+        let ticks = stream::interval(Duration::from_secs(60))
+            .take(3)
+            .count()
+            .await;
+        assert_eq!(ticks, 3);
+    });
+
+    assert_eq!(sim.now() - start, Duration::from_secs(3600 + 60 + 60 + 180));
+    assert!(real_start.elapsed() < Duration::from_secs(60));
+}
+
+#[test]
+fn timers_fire_in_deadline_order() {
+    let sim = Simulation::new(2);
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    sim.block_on(async {
+        let handles: Vec<_> = [3u64, 1, 2]
+            .iter()
+            .map(|&secs| {
+                let log = log.clone();
+                task::spawn(async move {
+                    task::sleep(Duration::from_secs(secs)).await;
+                    log.lock().unwrap().push(secs);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await;
+        }
+    });
+
+    assert_eq!(*log.lock().unwrap(), vec![1, 2, 3]);
+}
+
+/// Runs tasks that interleave at yield points and returns the order they finished in.
+fn run(seed: u64) -> Vec<usize> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    Simulation::new(seed).block_on(async {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let log = log.clone();
+                task::spawn(async move {
+                    for _ in 0..3 {
+                        task::yield_now().await;
+                    }
+                    log.lock().unwrap().push(i);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await;
+        }
+    });
+
+    let log = log.lock().unwrap().clone();
+    log
+}
+
+#[test]
+fn same_seed_same_schedule() {
+    for seed in 0..10 {
+        assert_eq!(run(seed), run(seed));
+    }
+
+    // Different seeds explore different schedules.
+    let mut schedules: Vec<_> = (0..10).map(run).collect();
+    schedules.sort();
+    schedules.dedup();
+    assert!(schedules.len() > 1);
+}
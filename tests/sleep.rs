@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use async_std::future;
+use async_std::task::{self, Simulation};
+
+#[test]
+fn reset_pushes_deadline_forward() {
+    task::block_on(async {
+        let start = Instant::now();
+        let mut sleep = task::sleep(Duration::from_millis(10));
+
+        // Keep pushing the deadline forward as if there was activity.
+        for _ in 0..5 {
+            let deadline = Instant::now() + Duration::from_millis(20);
+            sleep.reset(deadline);
+            assert_eq!(sleep.deadline(), deadline);
+            let res = future::timeout(Duration::from_millis(5), &mut sleep).await;
+            assert!(res.is_err());
+        }
+
+        (&mut sleep).await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    })
+}
+
+#[test]
+fn reset_to_earlier_deadline() {
+    task::block_on(async {
+        let start = Instant::now();
+        let mut sleep = task::sleep(Duration::from_secs(60));
+        let res = future::timeout(Duration::from_millis(5), &mut sleep).await;
+        assert!(res.is_err());
+
+        sleep.reset(Instant::now() + Duration::from_millis(10));
+        sleep.await;
+        assert!(start.elapsed() < Duration::from_secs(30));
+    })
+}
+
+#[test]
+fn deadlines_follow_virtual_clock() {
+    let sim = Simulation::new(7);
+    let deadline = sim.now() + Duration::from_secs(3600);
+
+    let res = sim.block_on(async move {
+        task::sleep_until(deadline).await;
+        future::timeout_at(deadline + Duration::from_secs(1), future::pending::<()>()).await
+    });
+
+    assert!(res.is_err());
+    assert_eq!(sim.now(), deadline + Duration::from_secs(1));
+}
@@ -0,0 +1,181 @@
+use async_std::io;
+use async_std::net::{TcpListener, TcpStream};
+use async_std::prelude::*;
+use async_std::task;
+
+async fn pair() -> io::Result<(TcpStream, TcpStream)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let client = TcpStream::connect(listener.local_addr()?).await?;
+    let (server, _) = listener.accept().await?;
+    Ok((client, server))
+}
+
+#[test]
+fn split_reads_and_writes_concurrently() -> io::Result<()> {
+    task::block_on(async {
+        let (mut client, mut server) = pair().await?;
+
+        let (mut reader, mut writer) = client.split();
+        let write = async {
+            writer.write_all(b"ping").await?;
+            futures::io::AsyncWriteExt::close(&mut writer).await
+        };
+        let echo = async {
+            let (mut reader, mut writer) = server.split();
+            let copied = io::copy(&mut reader, &mut writer).await?;
+            futures::io::AsyncWriteExt::close(&mut writer).await?;
+            io::Result::Ok(copied)
+        };
+        let read = async {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).await?;
+            io::Result::Ok(buf)
+        };
+
+        let ((_, copied), buf) = write.join(echo).join(read).await;
+        assert_eq!(copied?, 4);
+        assert_eq!(buf?, b"ping");
+        Ok(())
+    })
+}
+
+#[test]
+fn into_split_across_tasks() -> io::Result<()> {
+    task::block_on(async {
+        let (client, mut server) = pair().await?;
+        let (mut reader, mut writer) = client.into_split();
+
+        let handle = task::spawn(async move {
+            writer.write_all(b"hello").await?;
+            io::Result::Ok(())
+        });
+        handle.await?;
+
+        // Dropping the write half shut down the write side.
+        let mut buf = Vec::new();
+        server.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"hello");
+
+        // The read half stays open.
+        server.write_all(b"world").await?;
+        let mut buf = [0; 5];
+        reader.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"world");
+        Ok(())
+    })
+}
+
+#[test]
+fn reunite() -> io::Result<()> {
+    task::block_on(async {
+        let (a, mut peer) = pair().await?;
+        let (b, _) = pair().await?;
+        let addr = a.local_addr()?;
+
+        let (a_read, a_write) = a.into_split();
+        let (b_read, b_write) = b.into_split();
+
+        // Halves of different streams are handed back.
+        let err = a_read.reunite(b_write).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tried to reunite halves of different streams"
+        );
+        let a_read = err.0;
+
+        // Reuniting doesn't shut down the write side.
+        let mut stream = a_write.reunite(a_read).unwrap();
+        assert_eq!(stream.local_addr()?, addr);
+        stream.write_all(b"still open").await?;
+
+        let mut buf = [0; 10];
+        peer.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"still open");
+        drop(b_read);
+        Ok(())
+    })
+}
+
+#[test]
+fn write_half_without_shutdown_on_drop() -> io::Result<()> {
+    task::block_on(async {
+        let (client, mut server) = pair().await?;
+        let (_reader, mut writer) = client.clone().into_split();
+        writer.set_shutdown_on_drop(false);
+        writer.write_all(b"one").await?;
+        drop(writer);
+
+        // The connection stays writable through the original stream.
+        (&client).write_all(b"two").await?;
+        let mut buf = [0; 6];
+        server.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"onetwo");
+        Ok(())
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_split() -> io::Result<()> {
+    use async_std::os::unix::net::UnixStream;
+
+    task::block_on(async {
+        let (mut a, mut b) = UnixStream::pair()?;
+
+        let (mut reader, mut writer) = a.split();
+        writer.write_all(b"ping").await?;
+        futures::io::AsyncWriteExt::close(&mut writer).await?;
+
+        let mut buf = Vec::new();
+        b.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"ping");
+
+        b.write_all(b"pong").await?;
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"pong");
+        Ok(())
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_into_split() -> io::Result<()> {
+    use std::os::unix::io::IntoRawFd;
+
+    use async_std::os::unix::net::UnixStream;
+
+    task::block_on(async {
+        let (a, mut b) = UnixStream::pair()?;
+        let (c, _) = UnixStream::pair()?;
+
+        let (mut reader, mut writer) = a.into_split();
+        let handle = task::spawn(async move {
+            writer.write_all(b"ping").await?;
+            io::Result::Ok(writer)
+        });
+        let writer = handle.await?;
+
+        b.write_all(b"pong").await?;
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"pong");
+
+        // Halves of different streams are handed back.
+        let (c_read, c_write) = c.into_split();
+        let err = reader.reunite(c_write).unwrap_err();
+        let reader = err.0;
+        drop(c_read);
+
+        // Reuniting doesn't shut down the write side.
+        let mut stream = writer.reunite(reader).unwrap();
+        stream.write_all(b"ping").await?;
+        let mut buf = [0; 8];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"pingping");
+
+        // The reunited stream owns the socket again.
+        assert!(stream.into_raw_fd() >= 0);
+        Ok(())
+    })
+}
@@ -1,12 +1,13 @@
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use pin_project_lite::pin_project;
 
 use async_std::prelude::*;
-use async_std::stream;
+use async_std::stream::{self, DelayQueue, MissedTickBehavior};
 use async_std::sync::channel;
-use async_std::task;
+use async_std::task::{self, Simulation};
 
 #[test]
 /// Checks that streams are merged fully even if one of the components
@@ -98,3 +99,72 @@ fn merge_works_with_unfused_streams() {
     });
     assert_eq!(xs, vec![92, 92]);
 }
+
+/// Collects ticks of an interval that lags behind by 35 seconds after the first tick.
+fn lagging_ticks(behavior: MissedTickBehavior) -> Vec<Duration> {
+    let sim = Simulation::new(0);
+    let start = sim.now();
+
+    let ticks: Vec<Instant> = sim.block_on(async move {
+        let mut interval = stream::interval_at(start, Duration::from_secs(10));
+        interval.set_missed_tick_behavior(behavior);
+
+        let mut ticks = vec![interval.next().await.unwrap()];
+        task::sleep(Duration::from_secs(35)).await;
+        for _ in 0..3 {
+            ticks.push(interval.next().await.unwrap());
+        }
+        ticks
+    });
+    ticks.into_iter().map(|t| t - start).collect()
+}
+
+#[test]
+fn interval_missed_tick_behavior() {
+    let secs = |s: &[u64]| {
+        s.iter()
+            .map(|&s| Duration::from_secs(s))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        lagging_ticks(MissedTickBehavior::Burst),
+        secs(&[0, 10, 20, 30])
+    );
+    assert_eq!(
+        lagging_ticks(MissedTickBehavior::Delay),
+        secs(&[0, 10, 45, 55])
+    );
+    assert_eq!(
+        lagging_ticks(MissedTickBehavior::Skip),
+        secs(&[0, 10, 40, 50])
+    );
+}
+
+#[test]
+fn delay_queue_yields_expired_items() {
+    let sim = Simulation::new(0);
+    let start = sim.now();
+
+    let expired = sim.block_on(async move {
+        let mut queue = DelayQueue::new();
+        let a = queue.insert("a", Duration::from_secs(30));
+        queue.insert("b", Duration::from_secs(10));
+        let c = queue.insert("c", Duration::from_secs(20));
+
+        assert!(queue.reset(&a, Duration::from_secs(5)));
+        assert_eq!(queue.remove(&c), Some("c"));
+        assert_eq!(queue.remove(&c), None);
+        assert!(!queue.reset(&c, Duration::from_secs(1)));
+
+        let mut expired = Vec::new();
+        while let Some(item) = queue.next().await {
+            expired.push(item);
+        }
+        assert!(queue.deadline(&a).is_none());
+        expired
+    });
+
+    assert_eq!(expired, ["a", "b"]);
+    assert_eq!(sim.now() - start, Duration::from_secs(10));
+}
@@ -0,0 +1,215 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_std::future;
+use async_std::task::{self, TaskGroup};
+
+/// Sets a flag when dropped.
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Increments a counter when dropped.
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn join_outputs_in_order() {
+    task::block_on(async {
+        let mut group = TaskGroup::new();
+        for i in 0..10u64 {
+            group.spawn(async move {
+                task::sleep(Duration::from_millis(10 - i)).await;
+                i
+            });
+        }
+        assert_eq!(group.len(), 10);
+
+        let outputs = group.join().await.unwrap();
+        assert_eq!(outputs, (0..10).collect::<Vec<_>>());
+    });
+}
+
+#[test]
+fn join_empty() {
+    task::block_on(async {
+        let group = TaskGroup::<()>::new();
+        assert!(group.is_empty());
+        assert_eq!(group.join().await.unwrap(), vec![]);
+    });
+}
+
+#[test]
+fn panic_cancels_siblings() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let mut group = TaskGroup::new();
+        group.spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+        });
+        group.spawn(async {
+            task::sleep(Duration::from_millis(10)).await;
+            panic!("boom");
+        });
+
+        let err = group.join().await.unwrap_err();
+        assert!(err.is_panic());
+
+        // The sibling has finished by the time `join` returns.
+        assert!(dropped.load(Ordering::SeqCst));
+    });
+}
+
+#[test]
+fn panic_cancels_siblings_without_join() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let mut group = TaskGroup::new();
+        group.spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+        });
+        group.spawn(async { panic!("boom") });
+
+        // The sibling is cancelled even though nobody is waiting on the group.
+        for _ in 0..100 {
+            if dropped.load(Ordering::SeqCst) {
+                let err = group.join().await.unwrap_err();
+                assert!(err.is_panic());
+                return;
+            }
+            task::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("the sibling was not cancelled");
+    });
+}
+
+#[test]
+fn try_join_outputs_in_order() {
+    task::block_on(async {
+        let mut group = TaskGroup::<Result<u64, ()>>::new();
+        for i in 0..10u64 {
+            group.spawn(async move {
+                task::sleep(Duration::from_millis(10 - i)).await;
+                Ok(i)
+            });
+        }
+
+        let outputs = group.try_join().await.unwrap();
+        assert_eq!(outputs, Ok((0..10).collect::<Vec<_>>()));
+    });
+}
+
+#[test]
+fn try_join_error_cancels_siblings() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let mut group = TaskGroup::new();
+        group.spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+            Ok(())
+        });
+        group.spawn(async {
+            task::sleep(Duration::from_millis(10)).await;
+            Err("boom")
+        });
+
+        let res = future::timeout(Duration::from_secs(1), group.try_join()).await;
+        assert_eq!(res.unwrap().unwrap(), Err("boom"));
+
+        // The sibling has finished by the time `try_join` returns.
+        assert!(dropped.load(Ordering::SeqCst));
+    });
+}
+
+#[test]
+fn try_join_panic() {
+    task::block_on(async {
+        let mut group = TaskGroup::<Result<(), ()>>::new();
+        group.spawn(future::pending());
+        group.spawn(async { panic!("boom") });
+
+        let err = group.try_join().await.unwrap_err();
+        assert!(err.is_panic());
+    });
+}
+
+#[test]
+fn cancel_waits_for_children() {
+    task::block_on(async {
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let mut group = TaskGroup::new();
+        for _ in 0..10 {
+            let counter = DropCounter(count.clone());
+            group.spawn(async move {
+                let _counter = counter;
+                future::pending::<()>().await;
+            });
+        }
+        task::sleep(Duration::from_millis(10)).await;
+
+        group.cancel().await;
+        assert_eq!(count.load(Ordering::SeqCst), 10);
+    });
+}
+
+#[test]
+fn drop_cancels_children() {
+    task::block_on(async {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(dropped.clone());
+
+        let mut group = TaskGroup::new();
+        group.spawn(async move {
+            let _flag = flag;
+            future::pending::<()>().await;
+        });
+        drop(group);
+
+        for _ in 0..100 {
+            if dropped.load(Ordering::SeqCst) {
+                return;
+            }
+            task::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("the child was not cancelled");
+    });
+}
+
+#[test]
+fn builder_spawn_in() {
+    task::block_on(async {
+        let parent = task::current().id();
+
+        let mut group = TaskGroup::new();
+        task::Builder::new()
+            .name("child".to_string())
+            .spawn_in(&mut group, async move {
+                let task = task::current();
+                assert_ne!(task.id(), parent);
+                task.name().unwrap().to_string()
+            })
+            .unwrap();
+
+        assert_eq!(group.join().await.unwrap(), vec!["child".to_string()]);
+    });
+}
@@ -116,3 +116,45 @@ fn cloned_streams() -> io::Result<()> {
         Ok(())
     })
 }
+
+#[test]
+fn many_connections() -> io::Result<()> {
+    task::block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+
+        // Echo from tasks spread over the worker threads, which register their sockets in
+        // different shards of the reactor.
+        task::spawn(async move {
+            let mut incoming = listener.incoming();
+            while let Some(stream) = incoming.next().await {
+                let stream = stream?;
+                task::spawn(async move {
+                    let (reader, mut writer) = &mut (&stream, &stream);
+                    io::copy(reader, &mut writer).await
+                });
+            }
+            io::Result::Ok(())
+        });
+
+        let clients: Vec<_> = (0..64)
+            .map(|i| {
+                task::spawn(async move {
+                    let mut stream = TcpStream::connect(&addr).await?;
+                    let msg = format!("hello from {}", i);
+                    stream.write_all(msg.as_bytes()).await?;
+
+                    let mut buf = vec![0; msg.len()];
+                    stream.read_exact(&mut buf).await?;
+                    assert_eq!(buf, msg.as_bytes());
+                    io::Result::Ok(())
+                })
+            })
+            .collect();
+
+        for client in clients {
+            client.await?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,153 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_std::io;
+use async_std::net::{TcpSocket, TcpStream};
+use async_std::prelude::*;
+use async_std::task;
+
+fn localhost() -> SocketAddr {
+    "127.0.0.1:0".parse().unwrap()
+}
+
+#[test]
+fn listen_and_connect() -> io::Result<()> {
+    task::block_on(async {
+        let socket = TcpSocket::new_v4()?;
+        socket.set_reuseaddr(true)?;
+        assert!(socket.reuseaddr()?);
+        socket.bind(localhost())?;
+        let listener = socket.listen(128)?;
+        let addr = listener.local_addr()?;
+
+        // Connect from a local address chosen before connecting.
+        let socket = TcpSocket::new_for_addr(addr)?;
+        socket.bind(localhost())?;
+        let local = socket.local_addr()?;
+        let mut stream = socket.connect(addr).await?;
+        assert_eq!(stream.local_addr()?, local);
+
+        let (mut peer, peer_addr) = listener.accept().await?;
+        assert_eq!(peer_addr, local);
+
+        stream.write_all(b"ping").await?;
+        let mut buf = [0; 4];
+        peer.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"ping");
+        Ok(())
+    })
+}
+
+#[test]
+fn connect_refused() -> io::Result<()> {
+    task::block_on(async {
+        // Find a port nobody listens on.
+        let addr = {
+            let socket = TcpSocket::new_v4()?;
+            socket.bind(localhost())?;
+            socket.local_addr()?
+        };
+
+        let err = TcpSocket::new_v4()?.connect(addr).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+        Ok(())
+    })
+}
+
+#[test]
+fn buffer_sizes() -> io::Result<()> {
+    let socket = TcpSocket::new_v4()?;
+    socket.set_recv_buffer_size(64 * 1024)?;
+    socket.set_send_buffer_size(64 * 1024)?;
+
+    // The operating system may round the sizes up.
+    assert!(socket.recv_buffer_size()? >= 64 * 1024);
+    assert!(socket.send_buffer_size()? >= 64 * 1024);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn reuseport() -> io::Result<()> {
+    task::block_on(async {
+        let first = TcpSocket::new_v4()?;
+        first.set_reuseport(true)?;
+        assert!(first.reuseport()?);
+        first.bind(localhost())?;
+        let addr = first.local_addr()?;
+        let _first = first.listen(128)?;
+
+        // A second listener can bind to the same address.
+        let second = TcpSocket::new_v4()?;
+        second.set_reuseport(true)?;
+        second.bind(addr)?;
+        let _second = second.listen(128)?;
+
+        TcpStream::connect(addr).await?;
+        Ok(())
+    })
+}
+
+#[test]
+fn only_v6() -> io::Result<()> {
+    let socket = TcpSocket::new_v6()?;
+    socket.set_only_v6(true)?;
+    assert!(socket.only_v6()?);
+    socket.set_only_v6(false)?;
+    assert!(!socket.only_v6()?);
+    Ok(())
+}
+
+#[test]
+fn stream_options() -> io::Result<()> {
+    task::block_on(async {
+        let listener = TcpSocket::new_v4()?;
+        listener.bind(localhost())?;
+        let listener = listener.listen(128)?;
+        let stream = TcpStream::connect(listener.local_addr()?).await?;
+
+        assert_eq!(stream.keepalive()?, None);
+        stream.set_keepalive(Some(Duration::from_secs(60)))?;
+        assert_eq!(stream.keepalive()?, Some(Duration::from_secs(60)));
+        stream.set_keepalive(None)?;
+        assert_eq!(stream.keepalive()?, None);
+
+        stream.set_linger(Some(Duration::from_secs(0)))?;
+        assert_eq!(stream.linger()?, Some(Duration::from_secs(0)));
+
+        stream.set_recv_buffer_size(64 * 1024)?;
+        stream.set_send_buffer_size(64 * 1024)?;
+        assert!(stream.recv_buffer_size()? >= 64 * 1024);
+        assert!(stream.send_buffer_size()? >= 64 * 1024);
+
+        assert!(stream.take_error()?.is_none());
+        Ok(())
+    })
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn linux_stream_options() -> io::Result<()> {
+    task::block_on(async {
+        let listener = TcpSocket::new_v4()?;
+        listener.bind(localhost())?;
+        let listener = listener.listen(128)?;
+        let stream = TcpStream::connect(listener.local_addr()?).await?;
+
+        stream.set_keepalive(Some(Duration::from_secs(60)))?;
+        stream.set_keepalive_interval(Duration::from_secs(10))?;
+        stream.set_keepalive_retries(3)?;
+        assert_eq!(stream.keepalive()?, Some(Duration::from_secs(60)));
+        assert_eq!(stream.keepalive_interval()?, Duration::from_secs(10));
+        assert_eq!(stream.keepalive_retries()?, 3);
+
+        stream.set_user_timeout(Some(Duration::from_secs(30)))?;
+        assert_eq!(stream.user_timeout()?, Some(Duration::from_secs(30)));
+        stream.set_user_timeout(None)?;
+        assert_eq!(stream.user_timeout()?, None);
+
+        stream.set_quickack(true)?;
+        assert!(stream.quickack()?);
+        Ok(())
+    })
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use async_std::future;
+use async_std::task;
+
+#[test]
+fn many_timers_fire_after_deadlines() {
+    task::block_on(async {
+        let start = Instant::now();
+
+        // Spread deadlines over several levels of the timing wheel.
+        let handles: Vec<_> = (0..1000u64)
+            .map(|i| {
+                let dur = Duration::from_millis((i * 7919) % 300);
+                task::spawn(async move {
+                    task::sleep(dur).await;
+                    (dur, start.elapsed())
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (dur, elapsed) = handle.await;
+            assert!(elapsed >= dur);
+        }
+    })
+}
+
+#[test]
+fn cancelled_timers_do_not_fire() {
+    task::block_on(async {
+        // Thousands of pending timeouts that get cancelled when the futures complete.
+        for _ in 0..10_000 {
+            let res = future::timeout(Duration::from_secs(60), async { 1 }).await;
+            assert_eq!(res, Ok(1));
+        }
+
+        let start = Instant::now();
+        let res = future::timeout(Duration::from_millis(50), future::pending::<()>()).await;
+        assert!(res.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    })
+}
+
+#[test]
+fn earlier_timer_wakes_up_driver() {
+    task::block_on(async {
+        // The driver is sleeping until the long timer when the short one is registered.
+        let long = task::spawn(task::sleep(Duration::from_secs(10)));
+        task::sleep(Duration::from_millis(10)).await;
+
+        let start = Instant::now();
+        task::sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(long);
+    })
+}